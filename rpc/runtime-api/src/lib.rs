@@ -0,0 +1,44 @@
+//! Runtime API definition for oracle pallet
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use rstd::prelude::Vec;
+
+pub use pallet_oracle::{
+    Aggregation, ClockSource, Dispersion, OracleInfo, Part, PeriodInfo, SourceMode, SourceStats,
+    ValueInfo,
+};
+
+sp_api::decl_runtime_apis! {
+    pub trait OracleApi<OracleId, AccountId, ValueType, Moment> where
+        OracleId: Codec,
+        AccountId: Codec,
+        ValueType: Codec,
+        Moment: Codec,
+    {
+        /// Ids of all created oracles
+        fn oracles() -> Vec<OracleId>;
+
+        /// Metadata of oracle
+        fn oracle_info(oracle_id: OracleId) -> Option<OracleInfo<AccountId, Moment>>;
+
+        /// All external values of oracle
+        fn values(oracle_id: OracleId) -> Vec<ValueInfo<ValueType, Moment>>;
+
+        /// External value of oracle by id
+        fn value(oracle_id: OracleId, value_id: u8) -> Option<ValueInfo<ValueType, Moment>>;
+
+        /// External value of oracle by name
+        fn value_by_name(oracle_id: OracleId, name: Vec<u8>) -> Option<ValueInfo<ValueType, Moment>>;
+
+        /// Current period number and part of oracle
+        fn period(oracle_id: OracleId) -> Option<PeriodInfo<Moment>>;
+
+        /// Current sources of oracle
+        fn sources(oracle_id: OracleId) -> Vec<AccountId>;
+
+        /// Statistics of source work in oracle
+        fn source_stats(oracle_id: OracleId, source: AccountId) -> SourceStats;
+    }
+}
@@ -0,0 +1,215 @@
+//! RPC interface for oracle pallet
+
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_oracle_rpc_runtime_api::OracleApi as OracleRuntimeApi;
+use pallet_oracle_rpc_runtime_api::{OracleInfo, PeriodInfo, SourceStats, ValueInfo};
+
+/// Runtime call failed
+const RUNTIME_ERROR: i64 = 1;
+
+#[rpc]
+pub trait OracleApi<BlockHash, OracleId, AccountId, ValueType, Moment> {
+    /// Ids of all created oracles
+    #[rpc(name = "oracle_list")]
+    fn oracles(&self, at: Option<BlockHash>) -> Result<Vec<OracleId>>;
+
+    /// Metadata of oracle
+    #[rpc(name = "oracle_info")]
+    fn oracle_info(
+        &self,
+        oracle_id: OracleId,
+        at: Option<BlockHash>,
+    ) -> Result<Option<OracleInfo<AccountId, Moment>>>;
+
+    /// All external values of oracle
+    #[rpc(name = "oracle_values")]
+    fn values(
+        &self,
+        oracle_id: OracleId,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<ValueInfo<ValueType, Moment>>>;
+
+    /// External value of oracle by id
+    #[rpc(name = "oracle_value")]
+    fn value(
+        &self,
+        oracle_id: OracleId,
+        value_id: u8,
+        at: Option<BlockHash>,
+    ) -> Result<Option<ValueInfo<ValueType, Moment>>>;
+
+    /// External value of oracle by name
+    #[rpc(name = "oracle_valueByName")]
+    fn value_by_name(
+        &self,
+        oracle_id: OracleId,
+        name: String,
+        at: Option<BlockHash>,
+    ) -> Result<Option<ValueInfo<ValueType, Moment>>>;
+
+    /// Current period number and part of oracle
+    #[rpc(name = "oracle_period")]
+    fn period(
+        &self,
+        oracle_id: OracleId,
+        at: Option<BlockHash>,
+    ) -> Result<Option<PeriodInfo<Moment>>>;
+
+    /// Current sources of oracle
+    #[rpc(name = "oracle_sources")]
+    fn sources(&self, oracle_id: OracleId, at: Option<BlockHash>) -> Result<Vec<AccountId>>;
+
+    /// Statistics of source work in oracle
+    #[rpc(name = "oracle_sourceStats")]
+    fn source_stats(
+        &self,
+        oracle_id: OracleId,
+        source: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<SourceStats>;
+}
+
+/// Implementation of oracle RPC over runtime API
+pub struct Oracle<C, B> {
+    client: Arc<C>,
+    _marker: PhantomData<B>,
+}
+
+impl<C, B> Oracle<C, B> {
+    pub fn new(client: Arc<C>) -> Self {
+        Oracle {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+fn runtime_error(error: impl Debug) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(RUNTIME_ERROR),
+        message: "Unable to query oracle.".into(),
+        data: Some(format!("{:?}", error).into()),
+    }
+}
+
+impl<C, Block, OracleId, AccountId, ValueType, Moment>
+    OracleApi<<Block as BlockT>::Hash, OracleId, AccountId, ValueType, Moment> for Oracle<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static,
+    C: ProvideRuntimeApi,
+    C: HeaderBackend<Block>,
+    C::Api: OracleRuntimeApi<Block, OracleId, AccountId, ValueType, Moment>,
+    OracleId: Codec,
+    AccountId: Codec,
+    ValueType: Codec,
+    Moment: Codec,
+{
+    fn oracles(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<OracleId>> {
+        self.client
+            .runtime_api()
+            .oracles(&self.block_id(at))
+            .map_err(runtime_error)
+    }
+
+    fn oracle_info(
+        &self,
+        oracle_id: OracleId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<OracleInfo<AccountId, Moment>>> {
+        self.client
+            .runtime_api()
+            .oracle_info(&self.block_id(at), oracle_id)
+            .map_err(runtime_error)
+    }
+
+    fn values(
+        &self,
+        oracle_id: OracleId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<ValueInfo<ValueType, Moment>>> {
+        self.client
+            .runtime_api()
+            .values(&self.block_id(at), oracle_id)
+            .map_err(runtime_error)
+    }
+
+    fn value(
+        &self,
+        oracle_id: OracleId,
+        value_id: u8,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<ValueInfo<ValueType, Moment>>> {
+        self.client
+            .runtime_api()
+            .value(&self.block_id(at), oracle_id, value_id)
+            .map_err(runtime_error)
+    }
+
+    fn value_by_name(
+        &self,
+        oracle_id: OracleId,
+        name: String,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<ValueInfo<ValueType, Moment>>> {
+        self.client
+            .runtime_api()
+            .value_by_name(&self.block_id(at), oracle_id, name.into_bytes())
+            .map_err(runtime_error)
+    }
+
+    fn period(
+        &self,
+        oracle_id: OracleId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<PeriodInfo<Moment>>> {
+        self.client
+            .runtime_api()
+            .period(&self.block_id(at), oracle_id)
+            .map_err(runtime_error)
+    }
+
+    fn sources(
+        &self,
+        oracle_id: OracleId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<AccountId>> {
+        self.client
+            .runtime_api()
+            .sources(&self.block_id(at), oracle_id)
+            .map_err(runtime_error)
+    }
+
+    fn source_stats(
+        &self,
+        oracle_id: OracleId,
+        source: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<SourceStats> {
+        self.client
+            .runtime_api()
+            .source_stats(&self.block_id(at), oracle_id, source)
+            .map_err(runtime_error)
+    }
+}
+
+impl<C, Block> Oracle<C, Block>
+where
+    Block: BlockT,
+    C: HeaderBackend<Block>,
+{
+    /// Block to query, best block by default
+    fn block_id(&self, at: Option<<Block as BlockT>::Hash>) -> BlockId<Block> {
+        BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash))
+    }
+}
@@ -0,0 +1,86 @@
+use codec::{Decode, Encode};
+use sp_arithmetic::Perbill;
+
+use crate::external_value::{get_deviation, ExternalValue, ValueArithmetic};
+
+/// Freezing of value, that moved too far from the previous one
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct CircuitBreaker<ValueType, Moment> {
+    /// Max deviation of calculated value from the previous one
+    pub threshold: Perbill,
+
+    /// Calculated value, that tripped the breaker and waits for confirmation of owner
+    pub tripped: Option<ExternalValue<ValueType, Moment>>,
+}
+
+impl<ValueType: ValueArithmetic, Moment> CircuitBreaker<ValueType, Moment> {
+    pub fn new(threshold: Perbill) -> Self {
+        CircuitBreaker {
+            threshold,
+            tripped: None,
+        }
+    }
+
+    /// Value is frozen until confirmation of owner
+    pub fn is_tripped(&self) -> bool {
+        self.tripped.is_some()
+    }
+
+    /// Check calculated value against the previous confirmed one
+    ///
+    /// Return `calculated` back, if it replaces the previous value, or `None`, if it's held
+    /// until confirmation. Calculations in tripped breaker replace the held value.
+    pub fn check(
+        &mut self,
+        calculated: ExternalValue<ValueType, Moment>,
+        previous: Option<ValueType>,
+    ) -> Option<ExternalValue<ValueType, Moment>> {
+        let is_deviated = match (calculated.value, previous) {
+            (Some(value), Some(previous)) => get_deviation(value, previous) > self.threshold,
+            _ => false,
+        };
+
+        if self.is_tripped() || is_deviated {
+            self.tripped = Some(calculated);
+            None
+        } else {
+            Some(calculated)
+        }
+    }
+
+    /// Owner confirmed the value
+    ///
+    /// Return value held by the breaker
+    pub fn reset(&mut self) -> Option<ExternalValue<ValueType, Moment>> {
+        self.tripped.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::external_value::ExternalValue;
+    use sp_arithmetic::Perbill;
+
+    type CircuitBreaker = super::CircuitBreaker<u32, u32>;
+
+    #[test]
+    fn check() {
+        let mut breaker = CircuitBreaker::new(Perbill::from_percent(10));
+        let value = |value: u32| ExternalValue::new(value, 1);
+
+        assert_eq!(breaker.check(value(1000), None), Some(value(1000)));
+        assert_eq!(breaker.check(value(1100), Some(1000)), Some(value(1100)));
+        assert_eq!(breaker.check(value(900), Some(1000)), Some(value(900)));
+        assert!(!breaker.is_tripped());
+
+        assert_eq!(breaker.check(value(1101), Some(1000)), None);
+        assert!(breaker.is_tripped());
+        assert_eq!(breaker.check(value(1050), Some(1000)), None);
+        assert_eq!(breaker.tripped, Some(value(1050)));
+
+        assert_eq!(breaker.reset(), Some(value(1050)));
+        assert!(!breaker.is_tripped());
+        assert_eq!(breaker.check(value(1100), Some(1050)), Some(value(1100)));
+    }
+}
@@ -0,0 +1,54 @@
+use codec::{Decode, Encode};
+use rstd::marker::PhantomData;
+use sp_arithmetic::traits::{SaturatedConversion, UniqueSaturatedFrom};
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// Provider of current moment for oracle periods
+pub trait Clock<Moment> {
+    fn now() -> Moment;
+}
+
+/// Clock by `pallet_timestamp` moments
+pub struct TimestampClock<T>(PhantomData<T>);
+
+impl<T: timestamp::Trait> Clock<T::Moment> for TimestampClock<T> {
+    fn now() -> T::Moment {
+        timestamp::Module::<T>::get()
+    }
+}
+
+/// Clock by `system` block numbers, expressed in moment type
+pub struct BlockNumberClock<T>(PhantomData<T>);
+
+impl<T: timestamp::Trait> Clock<T::Moment> for BlockNumberClock<T> {
+    fn now() -> T::Moment {
+        let block_number: u128 = system::Module::<T>::block_number().saturated_into();
+        T::Moment::unique_saturated_from(block_number)
+    }
+}
+
+/// Clock selected for oracle
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub enum ClockSource {
+    Timestamp,
+    BlockNumber,
+}
+
+impl Default for ClockSource {
+    fn default() -> Self {
+        ClockSource::Timestamp
+    }
+}
+
+impl ClockSource {
+    /// Current moment by selected clock
+    pub fn now<T: timestamp::Trait>(self) -> T::Moment {
+        match self {
+            ClockSource::Timestamp => TimestampClock::<T>::now(),
+            ClockSource::BlockNumber => BlockNumberClock::<T>::now(),
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use codec::{Decode, Encode};
+use rstd::prelude::Vec;
+use sp_arithmetic::traits::{Bounded, UniqueSaturatedFrom, UniqueSaturatedInto};
+use sp_arithmetic::Perbill;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+use crate::external_value::ValueArithmetic;
+
+/// Max count of derived values in oracle
+pub const MAX_DERIVED_VALUES: usize = 32;
+
+/// Max count of formula inputs, e.g. values in basket
+pub const MAX_BASKET_LEN: usize = 16;
+
+/// Value of oracle, that is used as input of formula
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ValueRef<OracleId> {
+    pub oracle_id: OracleId,
+    pub value_id: u8,
+}
+
+/// Expression over values of oracles
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub enum Formula<OracleId> {
+    /// Product of values, e.g. EUR/JPY = EUR/USD * USD/JPY
+    Multiply(ValueRef<OracleId>, ValueRef<OracleId>),
+
+    /// Quotient of values, e.g. EUR/GBP = EUR/USD / GBP/USD
+    Divide(ValueRef<OracleId>, ValueRef<OracleId>),
+
+    /// Reciprocal of value, e.g. USD/EUR = 1 / EUR/USD
+    Invert(ValueRef<OracleId>),
+
+    /// Sum of values multiplied by their weights
+    Basket(Vec<(ValueRef<OracleId>, Perbill)>),
+}
+
+/// Value of oracle calculated on read by formula over other values
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct DerivedValue<OracleId> {
+    pub name: Vec<u8>,
+
+    /// Count of decimal digits, the value is `value / 10^decimals`
+    pub decimals: u8,
+
+    pub formula: Formula<OracleId>,
+}
+
+impl<OracleId: Clone> Formula<OracleId> {
+    /// Values used by formula in order of `calculate` inputs
+    pub fn get_inputs(&self) -> Vec<ValueRef<OracleId>> {
+        match self {
+            Formula::Multiply(left, right) | Formula::Divide(left, right) => {
+                [left.clone(), right.clone()].to_vec()
+            }
+            Formula::Invert(value) => [value.clone()].to_vec(),
+            Formula::Basket(items) => items.iter().map(|(value, _weight)| value.clone()).collect(),
+        }
+    }
+
+    /// Calculate formula with `decimals` digits over inputs with their decimals
+    ///
+    /// `None` on overflow, division by zero or wrong count of inputs
+    pub fn calculate(&self, inputs: &[(i128, u8)], decimals: u8) -> Option<i128> {
+        match (self, inputs) {
+            (Formula::Multiply(..), [(left, left_dec), (right, right_dec)]) => rescale(
+                left.checked_mul(*right)?,
+                left_dec.checked_add(*right_dec)?,
+                decimals,
+            ),
+            (Formula::Divide(..), [(left, left_dec), (right, right_dec)]) if *right != 0 => {
+                rescale(*left, *left_dec, decimals.checked_add(*right_dec)?)?.checked_div(*right)
+            }
+            (Formula::Invert(..), [(value, value_dec)]) if *value != 0 => {
+                rescale(1, 0, decimals.checked_add(*value_dec)?)?.checked_div(*value)
+            }
+            (Formula::Basket(items), inputs) if items.len() == inputs.len() => items
+                .iter()
+                .zip(inputs.iter())
+                .try_fold(0i128, |sum, ((_value, weight), (value, value_dec))| {
+                    let weighted = rescale(*value, *value_dec, decimals)?
+                        .checked_mul(weight.deconstruct() as i128)?
+                        / Perbill::one().deconstruct() as i128;
+                    sum.checked_add(weighted)
+                }),
+            _ => None,
+        }
+    }
+}
+
+/// Change count of decimal digits of value, extra digits are truncated
+pub fn rescale(value: i128, from: u8, to: u8) -> Option<i128> {
+    if to >= from {
+        value.checked_mul(10i128.checked_pow((to - from) as u32)?)
+    } else {
+        Some(
+            10i128
+                .checked_pow((from - to) as u32)
+                .map_or(0, |divider| value / divider),
+        )
+    }
+}
+
+/// Convert value to integer of calculations
+pub fn to_i128<ValueType: ValueArithmetic>(value: ValueType) -> i128 {
+    value.unique_saturated_into()
+}
+
+/// Convert integer of calculations to value, saturating at bounds of value type
+pub fn from_i128<ValueType: ValueArithmetic>(value: i128) -> ValueType {
+    if value < to_i128(ValueType::min_value()) {
+        ValueType::min_value()
+    } else {
+        ValueType::unique_saturated_from(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_arithmetic::Perbill;
+
+    type Formula = super::Formula<u32>;
+    type ValueRef = super::ValueRef<u32>;
+
+    fn value(value_id: u8) -> ValueRef {
+        ValueRef {
+            oracle_id: 0,
+            value_id,
+        }
+    }
+
+    #[test]
+    fn rescale() {
+        assert_eq!(super::rescale(12345, 2, 4), Some(1234500));
+        assert_eq!(super::rescale(12345, 4, 2), Some(123));
+        assert_eq!(super::rescale(-12345, 4, 2), Some(-123));
+        assert_eq!(super::rescale(1, 0, 40), None);
+        assert_eq!(super::rescale(1, 40, 0), Some(0));
+    }
+
+    #[test]
+    fn calculate() {
+        // EUR/USD 1.0850 * USD/JPY 150.25 = EUR/JPY 163.02
+        let multiply = Formula::Multiply(value(0), value(1));
+        assert_eq!(multiply.get_inputs(), vec![value(0), value(1)]);
+        assert_eq!(
+            multiply.calculate(&[(10850, 4), (15025, 2)], 2),
+            Some(16302)
+        );
+        assert_eq!(multiply.calculate(&[(10850, 4)], 2), None);
+
+        // EUR/USD 1.0850 / GBP/USD 1.2500 = EUR/GBP 0.8680
+        let divide = Formula::Divide(value(0), value(1));
+        assert_eq!(divide.calculate(&[(10850, 4), (12500, 4)], 4), Some(8680));
+        assert_eq!(divide.calculate(&[(10850, 4), (0, 4)], 4), None);
+
+        // 1 / EUR/USD 1.25 = USD/EUR 0.800
+        let invert = Formula::Invert(value(0));
+        assert_eq!(invert.calculate(&[(125, 2)], 3), Some(800));
+        assert_eq!(invert.calculate(&[(-125, 2)], 3), Some(-800));
+        assert_eq!(invert.calculate(&[(0, 2)], 3), None);
+
+        let basket = Formula::Basket(vec![
+            (value(0), Perbill::from_percent(60)),
+            (value(1), Perbill::from_percent(40)),
+        ]);
+        assert_eq!(basket.get_inputs(), vec![value(0), value(1)]);
+        assert_eq!(basket.calculate(&[(100, 0), (2000, 1)], 1), Some(1400));
+        assert_eq!(basket.calculate(&[(100, 0)], 1), None);
+    }
+
+    #[test]
+    fn convert() {
+        assert_eq!(super::from_i128::<u32>(-5), 0);
+        assert_eq!(super::from_i128::<u32>(i128::max_value()), u32::max_value());
+        assert_eq!(super::from_i128::<i8>(-500), i8::min_value());
+        assert_eq!(super::from_i128::<i64>(-500), -500);
+        assert_eq!(super::to_i128(u128::max_value()), i128::max_value());
+    }
+}
@@ -0,0 +1,85 @@
+use codec::{Decode, Encode};
+use rstd::prelude::Vec;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+use crate::external_value::{average, get_median, Median, ValueArithmetic};
+
+/// Spread of values pushed by sources around calculated value
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Dispersion<ValueType> {
+    /// Count of sources, whose values were used in calculation
+    pub sources: u32,
+
+    pub min: ValueType,
+    pub max: ValueType,
+
+    /// Interquartile range, difference between medians of upper and lower halves of values
+    pub iqr: ValueType,
+}
+
+impl<ValueType: ValueArithmetic> Dispersion<ValueType> {
+    /// Dispersion of values, `None` for empty values
+    pub fn new(mut values: Vec<ValueType>) -> Option<Self> {
+        values.sort();
+
+        let half = values.len() / 2;
+        let lower = Self::get_half_median(&values[..half]).or_else(|| values.first().copied())?;
+        let upper = Self::get_half_median(&values[values.len() - half..])
+            .or_else(|| values.last().copied())?;
+
+        Some(Dispersion {
+            sources: values.len() as u32,
+            min: *values.first()?,
+            max: *values.last()?,
+            iqr: upper.saturating_sub(lower),
+        })
+    }
+
+    fn get_half_median(values: &[ValueType]) -> Option<ValueType> {
+        match get_median(values.to_vec())? {
+            Median::Value(value) => Some(value),
+            Median::Pair(left, right) => Some(average(left, right)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    type Dispersion = super::Dispersion<i32>;
+
+    #[test]
+    fn new() {
+        assert_eq!(Dispersion::new(vec![]), None);
+        assert_eq!(
+            Dispersion::new(vec![7]),
+            Some(Dispersion {
+                sources: 1,
+                min: 7,
+                max: 7,
+                iqr: 0,
+            })
+        );
+        assert_eq!(
+            Dispersion::new(vec![10, 1, 8, 3, 5, 2]),
+            Some(Dispersion {
+                sources: 6,
+                min: 1,
+                max: 10,
+                iqr: 6,
+            })
+        );
+        assert_eq!(
+            Dispersion::new(vec![-4, 9, 0, 2, 100]),
+            Some(Dispersion {
+                sources: 5,
+                min: -4,
+                max: 100,
+                iqr: 56,
+            })
+        );
+    }
+}
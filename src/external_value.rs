@@ -1,6 +1,47 @@
 use codec::{Decode, Encode};
 use rstd::cmp::{Ord, Ordering};
+use rstd::collections::btree_map::BTreeMap;
+use rstd::ops::{Add, Div, Rem, Sub};
 use rstd::prelude::Vec;
+use sp_arithmetic::traits::{
+    Bounded, One, Saturating, UniqueSaturatedFrom, UniqueSaturatedInto, Zero,
+};
+use sp_arithmetic::Perbill;
+
+/// Arithmetic of external values, signed or unsigned integers
+pub trait ValueArithmetic:
+    Zero
+    + One
+    + Bounded
+    + Saturating
+    + Ord
+    + Copy
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + UniqueSaturatedInto<u128>
+    + UniqueSaturatedInto<i128>
+    + UniqueSaturatedFrom<i128>
+{
+}
+
+impl<T> ValueArithmetic for T where
+    T: Zero
+        + One
+        + Bounded
+        + Saturating
+        + Ord
+        + Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Div<Output = T>
+        + Rem<Output = T>
+        + UniqueSaturatedInto<u128>
+        + UniqueSaturatedInto<i128>
+        + UniqueSaturatedFrom<i128>
+{
+}
 
 /// Value or pair of value in vector
 #[derive(PartialEq)]
@@ -11,17 +52,97 @@ pub enum Median<T> {
 }
 
 /// Get median from ordered values
+///
+/// Single value is the median of itself, `None` only for empty values
 pub fn get_median<T: Ord + Copy>(mut values: Vec<T>) -> Option<Median<T>> {
     values.sort();
 
     let middle = values.len() / 2;
     match values.len() {
-        0 | 1 => None,
+        0 => None,
         len if len % 2 == 0 => Some(Median::Pair(values[middle - 1], values[middle])),
         _len => Some(Median::Value(values[middle])),
     }
 }
 
+/// Get value with the largest total weight of votes
+///
+/// `None` if there are no votes, the largest weight is shared by several values
+/// or it is less than `threshold` part of total weight
+pub fn get_weighted_majority<T: Ord + Copy>(
+    votes: Vec<(T, u128)>,
+    threshold: Perbill,
+) -> Option<T> {
+    let mut weights = BTreeMap::<T, u128>::new();
+    votes.into_iter().for_each(|(value, weight)| {
+        let total = weights.entry(value).or_insert(0);
+        *total = total.saturating_add(weight);
+    });
+
+    let total = weights
+        .values()
+        .fold(0u128, |total, weight| total.saturating_add(*weight));
+    let (winner, max) = weights
+        .iter()
+        .max_by_key(|(_value, weight)| **weight)
+        .map(|(value, weight)| (*value, *weight))?;
+
+    if total == 0 || weights.values().filter(|weight| **weight == max).count() > 1 {
+        return None;
+    }
+
+    if Perbill::from_rational_approximation(max, total) >= threshold {
+        Some(winner)
+    } else {
+        None
+    }
+}
+
+/// Absolute difference between two values
+///
+/// Saturates at max value for signed values of different signs
+pub fn abs_diff<T: ValueArithmetic>(left: T, right: T) -> T {
+    if left > right {
+        left.saturating_sub(right)
+    } else {
+        right.saturating_sub(left)
+    }
+}
+
+/// Average of two values without overflow, rounded toward zero for signed values
+pub fn average<T: ValueArithmetic>(left: T, right: T) -> T {
+    let two = T::one() + T::one();
+    if (left < T::zero()) != (right < T::zero()) {
+        // Sum of values of different signs can't overflow
+        (left + right) / two
+    } else {
+        left / two + right / two + (left % two + right % two) / two
+    }
+}
+
+/// Deviation of `value` from `target` relative to `target`
+///
+/// Saturates at 100%
+pub fn get_deviation<T: ValueArithmetic>(value: T, target: T) -> Perbill {
+    let diff: u128 = abs_diff(value, target).unique_saturated_into();
+    let base: u128 = abs_diff(target, T::zero()).unique_saturated_into();
+
+    if diff >= base {
+        if diff == 0 {
+            Perbill::zero()
+        } else {
+            Perbill::one()
+        }
+    } else {
+        Perbill::from_rational_approximation(diff, base)
+    }
+}
+
+/// Is `value` deviates from `target` not more than `tolerance`
+pub fn is_within_tolerance<T: ValueArithmetic>(value: T, target: T, tolerance: Perbill) -> bool {
+    get_deviation(value, target) <= tolerance
+}
+
 /// External (for blockchain) value
 #[derive(Encode, Decode, Clone, Eq, PartialEq, Default)]
 #[cfg_attr(feature = "std", derive(Debug))]
@@ -31,6 +152,11 @@ pub struct ExternalValue<ValueType, Moment> {
     /// Moment we last changed the value
     /// - None if value is empty
     pub last_changed: Option<Moment>,
+
+    /// Moment the value was observed off-chain by source, for calculated value - median
+    /// of observation moments of pushed values
+    /// - None if value is empty
+    pub observed: Option<Moment>,
 }
 
 impl<ValueType: Default + Eq + Ord + Clone, Moment: Default + Eq + Ord + Clone> Ord
@@ -58,18 +184,25 @@ impl<ValueType: Default + Eq + Ord + Clone, Moment: Default + Eq + Ord + Clone>
     pub fn new(value: ValueType, now: Moment) -> Self {
         ExternalValue {
             value: Some(value),
-            last_changed: Some(now),
+            last_changed: Some(now.clone()),
+            observed: Some(now),
         }
     }
 
     pub fn clean(&mut self) {
         self.value = None;
         self.last_changed = None;
+        self.observed = None;
     }
 
     pub fn update(&mut self, value: ValueType, now: Moment) {
+        self.update_observed(value, now.clone(), now);
+    }
+
+    pub fn update_observed(&mut self, value: ValueType, now: Moment, observed: Moment) {
         self.value = Some(value);
         self.last_changed = Some(now);
+        self.observed = Some(observed);
     }
 
     pub fn is_clean(&self) -> bool {
@@ -87,7 +220,11 @@ impl<ValueType: Default + Eq + Ord + Clone, Moment: Default + Eq + Ord + Clone>
 
 #[cfg(test)]
 mod tests {
-    use super::{get_median, Median};
+    use super::{
+        abs_diff, average, get_deviation, get_median, get_weighted_majority, is_within_tolerance,
+        Median,
+    };
+    use sp_arithmetic::Perbill;
 
     #[test]
     fn simple() {
@@ -95,4 +232,78 @@ mod tests {
         let median = array[5];
         assert_eq!(get_median(array), Some(Median::Value(median)));
     }
+
+    #[test]
+    fn edge_cases() {
+        assert_eq!(get_median(Vec::<u8>::new()), None);
+        assert_eq!(get_median(vec![42u8]), Some(Median::Value(42)));
+        assert_eq!(get_median(vec![50u8, 42]), Some(Median::Pair(42, 50)));
+        assert_eq!(get_median(vec![7u8, 7]), Some(Median::Pair(7, 7)));
+        assert_eq!(get_median(vec![3u8, 1, 2]), Some(Median::Value(2)));
+    }
+
+    #[test]
+    fn average_pair() {
+        assert_eq!(average(42u8, 50), 46);
+        assert_eq!(average(50u8, 42), 46);
+        assert_eq!(average(7u8, 7), 7);
+        assert_eq!(average(1u8, 2), 1);
+        assert_eq!(average(250u8, 254), 252);
+        assert_eq!(average(u8::max_value(), u8::max_value()), u8::max_value());
+
+        assert_eq!(average(-3i8, -5), -4);
+        assert_eq!(average(-3i8, 4), 0);
+        assert_eq!(average(-3i8, -2), -2);
+        assert_eq!(average(i8::min_value(), i8::min_value()), i8::min_value());
+        assert_eq!(average(i8::min_value(), i8::max_value()), 0);
+    }
+
+    #[test]
+    fn signed() {
+        assert_eq!(get_median(vec![-5i32, 3, -1]), Some(Median::Value(-1)));
+        assert_eq!(get_deviation(-90i32, -100), Perbill::from_percent(10));
+        assert_eq!(get_deviation(10i32, -100), Perbill::one());
+        assert_eq!(get_deviation(-5i32, 100), Perbill::one());
+        assert!(is_within_tolerance(-104i32, -100, Perbill::from_percent(5)));
+        assert_eq!(abs_diff(i8::min_value(), i8::max_value()), i8::max_value());
+    }
+
+    #[test]
+    fn weighted_majority() {
+        let half = Perbill::from_percent(50);
+        assert_eq!(get_weighted_majority(Vec::<(u8, u128)>::new(), half), None);
+        assert_eq!(get_weighted_majority(vec![(1u8, 0), (0, 0)], half), None);
+        assert_eq!(get_weighted_majority(vec![(1u8, 10), (0, 10)], half), None);
+        assert_eq!(
+            get_weighted_majority(vec![(1u8, 10), (0, 5), (0, 6)], half),
+            Some(0)
+        );
+        assert_eq!(
+            get_weighted_majority(vec![(1u8, 30), (0, 20), (2, 50)], half),
+            Some(2)
+        );
+
+        let supermajority = Perbill::from_rational_approximation(2u32, 3);
+        assert_eq!(
+            get_weighted_majority(vec![(1u8, 60), (0, 40)], supermajority),
+            None
+        );
+        assert_eq!(
+            get_weighted_majority(vec![(1u8, 70), (0, 30)], supermajority),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn deviation() {
+        assert_eq!(get_deviation(100u32, 100), Perbill::zero());
+        assert_eq!(get_deviation(90u32, 100), Perbill::from_percent(10));
+        assert_eq!(get_deviation(110u32, 100), Perbill::from_percent(10));
+        assert_eq!(get_deviation(300u32, 100), Perbill::one());
+        assert_eq!(get_deviation(0u32, 0), Perbill::zero());
+        assert_eq!(get_deviation(1u32, 0), Perbill::one());
+
+        assert!(is_within_tolerance(105u32, 100, Perbill::from_percent(5)));
+        assert!(!is_within_tolerance(106u32, 100, Perbill::from_percent(5)));
+    }
 }
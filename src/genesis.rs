@@ -0,0 +1,41 @@
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_arithmetic::Perbill;
+
+use crate::clock::ClockSource;
+use crate::oracle::SourceMode;
+use crate::period_handler::PeriodAlignment;
+
+/// Oracle created in genesis, oracles get ids in order of genesis config
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleGenesis<AccountId, AssetId, Moment, ValueType> {
+    pub owner: AccountId,
+    pub name: Vec<u8>,
+
+    /// Lower limit of the number of sources, at least one
+    pub source_limit: u8,
+
+    /// Part of sources, that must push value for calculation
+    pub quorum: Option<Perbill>,
+
+    pub period: Moment,
+    pub aggregate_period: Moment,
+
+    /// Asset with the help of which voting is carried out in tablescore
+    pub asset_id: AssetId,
+
+    pub values_names: Vec<Vec<u8>>,
+    pub values_decimals: Vec<u8>,
+
+    pub clock: ClockSource,
+    pub alignment: PeriodAlignment<Moment>,
+    pub source_mode: SourceMode,
+
+    /// Sources of `Fixed` source mode, must be empty for `Tablescore` mode
+    pub sources: Vec<AccountId>,
+
+    /// Initial calculated values, empty - values are not calculated
+    pub values: Vec<ValueType>,
+}
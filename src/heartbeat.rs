@@ -0,0 +1,68 @@
+use codec::{Decode, Encode};
+use sp_arithmetic::traits::SimpleArithmetic;
+
+/// Guarantee of value calculation at least every `periods` periods
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Heartbeat<Moment> {
+    /// Max count of periods without value calculation
+    pub periods: u32,
+
+    /// Period of the last value calculation or of the heartbeat setting
+    pub last_beat: Moment,
+
+    /// Heartbeat was missed and value is degraded until next calculation
+    pub is_missed: bool,
+}
+
+impl<Moment: SimpleArithmetic + Copy> Heartbeat<Moment> {
+    pub fn new(periods: u32, current_period: Moment) -> Self {
+        Heartbeat {
+            periods,
+            last_beat: current_period,
+            is_missed: false,
+        }
+    }
+
+    /// Value was calculated in `current_period`
+    pub fn beat(&mut self, current_period: Moment) {
+        self.last_beat = current_period;
+        self.is_missed = false;
+    }
+
+    pub fn is_expired(&self, current_period: Moment) -> bool {
+        current_period > self.last_beat + Moment::from(self.periods)
+    }
+
+    /// Mark heartbeat as missed if it expired
+    ///
+    /// Return true only when heartbeat became missed
+    pub fn check(&mut self, current_period: Moment) -> bool {
+        if !self.is_missed && self.is_expired(current_period) {
+            self.is_missed = true;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    type Heartbeat = super::Heartbeat<u32>;
+
+    #[test]
+    fn check() {
+        let mut heartbeat = Heartbeat::new(2, 10);
+
+        (10..=12).for_each(|period| assert!(!heartbeat.check(period)));
+        assert!(heartbeat.check(13));
+        assert!(heartbeat.is_missed);
+        assert!(!heartbeat.check(14));
+
+        heartbeat.beat(14);
+        assert!(!heartbeat.is_missed);
+        assert!(!heartbeat.check(16));
+        assert!(heartbeat.check(17));
+    }
+}
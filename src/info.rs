@@ -0,0 +1,111 @@
+use codec::{Decode, Encode};
+use rstd::prelude::Vec;
+use sp_arithmetic::Perbill;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+use crate::clock::ClockSource;
+use crate::derived::ValueRef;
+use crate::dispersion::Dispersion;
+use crate::oracle::{Aggregation, SourceMode};
+pub use crate::period_handler::Part;
+
+/// Metadata of oracle for read API
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct OracleInfo<AccountId, Moment> {
+    pub name: Vec<u8>,
+    pub owner: Option<AccountId>,
+
+    /// Lower limit of the number of sources
+    pub source_limit: u8,
+
+    /// Part of sources, that must push value for calculation
+    pub quorum: Option<Perbill>,
+
+    pub period: Moment,
+    pub aggregate_part: Moment,
+
+    /// Clock, that measures `period` and `aggregate_part`
+    pub clock: ClockSource,
+
+    /// How sources of oracle are selected
+    pub source_mode: SourceMode,
+
+    /// Oracle doesn't accept pushes and calculations
+    pub is_paused: bool,
+
+    /// Names of external values
+    pub values_names: Vec<Vec<u8>>,
+}
+
+/// External value of oracle for read API
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ValueInfo<ValueType, Moment> {
+    pub name: Vec<u8>,
+
+    /// Calculated value, `None` if value was never calculated
+    pub value: Option<ValueType>,
+
+    /// Finalised byte payload of value with `Hash` aggregation
+    pub payload: Option<Vec<u8>>,
+
+    /// Count of decimal digits, the value is `value / 10^decimals`
+    pub decimals: u8,
+
+    /// How values pushed by sources are aggregated
+    pub aggregation: Aggregation,
+    pub last_changed: Option<Moment>,
+
+    /// Median moment, when pushed values were observed by sources
+    pub observed: Option<Moment>,
+
+    /// Spread of values pushed by sources in the last calculation
+    pub dispersion: Option<Dispersion<ValueType>>,
+
+    /// Can the value be calculated right now
+    pub is_calculate_allowed: bool,
+
+    /// Value was not calculated in time according to its heartbeat
+    pub is_degraded: bool,
+
+    /// Oracle is paused, value is not updated
+    pub is_paused: bool,
+
+    /// Value is frozen by circuit breaker until confirmation of owner
+    pub is_frozen: bool,
+
+    /// Calculated value held by circuit breaker, `value` is the previous confirmed one
+    pub frozen_value: Option<ValueType>,
+}
+
+/// Current state of oracle period for read API
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PeriodInfo<Moment> {
+    /// Number of current period
+    pub number: Moment,
+
+    pub part: Part,
+}
+
+/// Value served to consumer by provider API
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ServedValue<OracleId, ValueType, Moment> {
+    /// Value of oracle, that actually served: requested one or one of its fallbacks
+    pub served_by: ValueRef<OracleId>,
+
+    pub value: ValueType,
+
+    /// Count of decimal digits of serving value, the value is `value / 10^decimals`
+    pub decimals: u8,
+
+    pub last_changed: Moment,
+}
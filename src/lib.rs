@@ -1,25 +1,71 @@
 #![allow(dead_code)]
-#![feature(rustc_private)] // decl_storage extra genesis bug
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::{decl_error, decl_event, decl_module, decl_storage, dispatch, Parameter};
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage, dispatch, ensure, Parameter,
+};
+use rstd::collections::btree_map::BTreeMap;
 use rstd::prelude::*;
-use sp_arithmetic::traits::{CheckedAdd, One, SimpleArithmetic};
-use sp_runtime::traits::{MaybeSerializeDeserialize, Member};
-use system::ensure_signed;
+use sp_arithmetic::traits::{
+    CheckedAdd, One, Saturating, SimpleArithmetic, UniqueSaturatedInto, Zero,
+};
+use sp_runtime::traits::{AccountIdConversion, Hash as HashT, MaybeSerializeDeserialize, Member};
+use sp_runtime::{ModuleId, Perbill};
+use system::{ensure_root, ensure_signed};
 
-use crate::oracle::OracleError as InternalError;
+use crate::oracle::{CalculationTrigger, OracleError as InternalError, PeriodData, Stakes};
 
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
 mod tests;
 
+mod breaker;
+mod clock;
+mod derived;
+mod dispersion;
 mod external_value;
+mod genesis;
+mod heartbeat;
+mod info;
+mod migration;
 mod oracle;
+mod payload;
+mod penalty;
 mod period_handler;
+mod reward;
+mod stats;
+mod subscription;
+mod update;
 
+pub use crate::clock::{BlockNumberClock, Clock, ClockSource, TimestampClock};
+pub use crate::derived::{DerivedValue, Formula, ValueRef, MAX_BASKET_LEN, MAX_DERIVED_VALUES};
+pub use crate::dispersion::Dispersion;
+pub use crate::external_value::ValueArithmetic;
+#[cfg(feature = "std")]
+pub use crate::genesis::OracleGenesis;
+pub use crate::info::{OracleInfo, Part, PeriodInfo, ServedValue, ValueInfo};
+pub use crate::migration::STORAGE_VERSION;
+pub use crate::oracle::{Aggregation, SourceMode, MAX_HISTORY_DEPTH};
+pub use crate::payload::MAX_PAYLOAD_LEN;
+pub use crate::penalty::OnSourcePenalty;
+pub use crate::period_handler::PeriodAlignment;
+pub use crate::stats::SourceStats;
+pub use crate::subscription::{Subscription, SubscriptionConfig, MAX_SUBSCRIPTION_PERIODS};
+pub use crate::update::OnOracleUpdate;
+
+use crate::derived::{from_i128, to_i128};
+use crate::payload::PayloadValue;
+use crate::penalty::{Bond, PenaltyConfig, SourcePenalty};
 use crate::period_handler::PeriodHandler;
+use crate::reward::RewardPool;
+use crate::subscription::PeriodFees;
+
+/// Account of pallet, that holds funds of all reward pools and subscription fees
+const MODULE_ID: ModuleId = ModuleId(*b"py/oracl");
+
+/// Max count of fallbacks after value in fallback chain
+pub const MAX_FALLBACK_DEPTH: usize = 4;
 
 type AccountId<T> = <T as system::Trait>::AccountId;
 
@@ -34,11 +80,19 @@ pub trait Trait:
         + Copy
         + SimpleArithmetic
         + MaybeSerializeDeserialize;
-    type ValueType: Default + Parameter + Member + Copy + SimpleArithmetic;
+    /// Type of external values, signed or unsigned integer
+    type ValueType: Default + Parameter + Member + ValueArithmetic;
+
+    /// Handler of sources penalized in oracles
+    type OnSourcePenalty: OnSourcePenalty<Self::OracleId, AccountId<Self>>;
+
+    /// Handler of values calculated in oracles
+    type OnOracleUpdate: OnOracleUpdate<Self::OracleId, Self::ValueType, Moment<Self>>;
 }
 
 type Moment<T> = <T as timestamp::Trait>::Moment;
 type AssetId<T> = <T as assets::Trait>::AssetId;
+type Balance<T> = <T as assets::Trait>::Balance;
 
 type Oracle<T> = crate::oracle::Oracle<
     <T as tablescore::Trait>::TableId,
@@ -52,6 +106,66 @@ decl_storage! {
     {
         pub Oracles get(fn oracles): map hasher(blake2_256) T::OracleId => Oracle<T>;
         OracleIdSequence get(fn next_oracle_id): T::OracleId;
+        /// Version of storage layout, storage is migrated to current version on runtime upgrade
+        StorageVersion get(fn storage_version) build(|_| migration::STORAGE_VERSION): u32;
+        OracleOwners get(fn oracle_owner): map hasher(blake2_256) T::OracleId => Option<T::AccountId>;
+
+        /// Data pushed in past periods by period number for late calculations
+        pub History get(fn history): double_map hasher(blake2_256) T::OracleId, hasher(blake2_256) Moment<T> => Option<PeriodData<T::AccountId, T::ValueType, Moment<T>>>;
+        /// Past periods of oracle with stored history, from older to newer
+        pub HistoryPeriods get(fn history_periods): map hasher(blake2_256) T::OracleId => Vec<Moment<T>>;
+
+        /// Values calculated for past periods by (value id, period number)
+        pub PastValues get(fn past_values): double_map hasher(blake2_256) T::OracleId, hasher(blake2_256) (u8, Moment<T>) => Option<T::ValueType>;
+
+        /// Byte payloads of values with `Hash` aggregation by value id
+        pub PayloadValues get(fn payload_value): double_map hasher(blake2_256) T::OracleId, hasher(blake2_256) u8 => PayloadValue<T::Hash, Moment<T>, T::AccountId>;
+
+        /// Values of oracle calculated on read by formulas over other values, by derived id
+        pub DerivedValues get(fn derived_values): map hasher(blake2_256) T::OracleId => BTreeMap<u8, DerivedValue<T::OracleId>>;
+        DerivedIdSequence get(fn next_derived_id): map hasher(blake2_256) T::OracleId => u8;
+
+        /// Values of other oracles, that serve consumers, when value is unavailable
+        pub Fallbacks get(fn fallback): double_map hasher(blake2_256) T::OracleId, hasher(blake2_256) u8 => Option<ValueRef<T::OracleId>>;
+        /// Max age of values served to consumers
+        pub MaxAges get(fn max_age): double_map hasher(blake2_256) T::OracleId, hasher(blake2_256) u8 => Option<Moment<T>>;
+
+        /// Emergency stop of pushes and calculations in all oracles
+        pub IsHalted get(fn is_halted): bool;
+
+        pub RewardPools get(fn reward_pool): map hasher(blake2_256) T::OracleId => Option<RewardPool<AssetId<T>, Balance<T>>>;
+        /// Not claimed rewards of sources
+        pub Rewards get(fn rewards): double_map hasher(blake2_256) T::OracleId, hasher(blake2_256) T::AccountId => Balance<T>;
+        /// The last period by value id, whose sources were rewarded and tracked in penalties
+        /// and statistics
+        pub RewardedPeriods get(fn rewarded_period): double_map hasher(blake2_256) T::OracleId, hasher(blake2_256) u8 => Option<Moment<T>>;
+
+        pub PenaltyConfigs get(fn penalty_config): map hasher(blake2_256) T::OracleId => Option<PenaltyConfig<Balance<T>>>;
+        pub Penalties get(fn penalties): double_map hasher(blake2_256) T::OracleId, hasher(blake2_256) T::AccountId => SourcePenalty;
+        /// Asset of source bonds, sources of oracle with bond asset must bond to push
+        pub BondAssets get(fn bond_asset): map hasher(blake2_256) T::OracleId => Option<AssetId<T>>;
+        pub Bonds get(fn bonds): double_map hasher(blake2_256) T::OracleId, hasher(blake2_256) T::AccountId => Option<Bond<AssetId<T>, Balance<T>>>;
+
+        /// Subscription settings of permissioned oracles, only subscribers can read them
+        pub SubscriptionConfigs get(fn subscription_config): map hasher(blake2_256) T::OracleId => Option<SubscriptionConfig<AssetId<T>, Balance<T>>>;
+        pub Subscriptions get(fn subscription): double_map hasher(blake2_256) T::OracleId, hasher(blake2_256) T::AccountId => Option<Subscription<Moment<T>>>;
+        /// Subscription fees by period number, that are shared among sources of the period
+        pub SubscriptionFees get(fn subscription_fees): double_map hasher(blake2_256) T::OracleId, hasher(blake2_256) Moment<T> => PeriodFees<Balance<T>>;
+        /// Periods of oracle with subscription fees, from older to newer
+        pub FeePeriods get(fn fee_periods): map hasher(blake2_256) T::OracleId => Vec<Moment<T>>;
+        /// Not claimed subscription fees of sources
+        pub Fees get(fn fees): double_map hasher(blake2_256) T::OracleId, hasher(blake2_256) T::AccountId => Balance<T>;
+
+        pub SourceStatistics get(fn source_stats): double_map hasher(blake2_256) T::OracleId, hasher(blake2_256) T::AccountId => SourceStats;
+    }
+    add_extra_genesis {
+        // Oracles with working price feeds from the chain start
+        config(oracles): Vec<OracleGenesis<T::AccountId, AssetId<T>, Moment<T>, T::ValueType>>;
+        build(|config: &GenesisConfig<T>| {
+            config.oracles.iter().cloned().for_each(|oracle| {
+                Module::<T>::create_genesis_oracle(oracle).expect("Genesis oracle must be valid");
+            });
+        });
     }
 }
 
@@ -62,9 +176,48 @@ decl_event!(
         OracleId = <T as Trait>::OracleId,
         ValueType = <T as Trait>::ValueType,
         ValueId = u8,
+        Balance = <T as assets::Trait>::Balance,
+        Moment = <T as timestamp::Trait>::Moment,
+        Hash = <T as system::Trait>::Hash,
     {
         OracleCreated(OracleId, AccountId),
+        /// Period parameters will change from the moment
+        PeriodChangeScheduled(OracleId, Moment),
+        OraclePaused(OracleId),
+        /// Oracle resumed with new period, that starts at the moment
+        OracleResumed(OracleId, Moment),
+        /// Pushes and calculations in all oracles are stopped (true) or allowed (false)
+        HaltedSet(bool),
+        /// Value moved too far from the previous one and is frozen until confirmation
+        CircuitBreakerTripped(OracleId, ValueId, ValueType),
+        ValueConfirmed(OracleId, ValueId),
+        /// Derived value added to oracle with id
+        DerivedValueAdded(OracleId, ValueId),
+        DerivedValueRemoved(OracleId, ValueId),
+        /// Fallback of value is set or removed
+        FallbackSet(OracleId, ValueId),
+        /// Max age of value is set or removed
+        MaxAgeSet(OracleId, ValueId),
+        /// Value calculated for past period with number
+        PastValueCalculated(OracleId, ValueId, Moment, ValueType),
+        /// Enough sources agreed on payload with the hash
+        PayloadFinalized(OracleId, ValueId, Hash),
         OracleUpdated(OracleId, ValueId, ValueType),
+        /// Value calculated early, because pushed values deviate from the last calculated one
+        DeviationUpdate(OracleId, ValueId, ValueType),
+        /// Value was not calculated in time and is degraded until next calculation
+        HeartbeatMissed(OracleId, ValueId),
+        RewardPoolFunded(OracleId, AccountId, Balance),
+        SourceRewarded(OracleId, AccountId, Balance),
+        RewardClaimed(OracleId, AccountId, Balance),
+        /// Consumer paid amount for subscription until period with number
+        Subscribed(OracleId, AccountId, Balance, Moment),
+        SubscriptionFeePaid(OracleId, AccountId, Balance),
+        FeesClaimed(OracleId, AccountId, Balance),
+        SourceBonded(OracleId, AccountId, Balance),
+        SourceUnbonded(OracleId, AccountId, Balance),
+        /// Source exceeded penalty limits, bond slashed by amount
+        SourcePenalized(OracleId, AccountId, Balance),
     }
 );
 
@@ -81,6 +234,41 @@ decl_error! {
         NotEnoughValues,
         NotCalculatedValue,
         AccountPermissionDenied,
+        NotOracleOwner,
+        NoRewardPool,
+        RewardPoolNotEmpty,
+        NothingToClaim,
+        WrongBondAsset,
+        NoBondAsset,
+        NoBond,
+        SourceIsActive,
+        WrongOracleId,
+        OraclePaused,
+        OracleNotPaused,
+        PalletHalted,
+        ValueFrozen,
+        ValueNotFrozen,
+        NoPeriodData,
+        WrongHistoryDepth,
+        WrongSourceLimit,
+        WrongQuorum,
+        NoMajority,
+        NotPayloadValue,
+        WrongPayloadLength,
+        WrongObservationTime,
+        WrongFormula,
+        FormulaError,
+        WrongInputClock,
+        TooManyDerivedValues,
+        DerivedIdOverflow,
+        ValueDegraded,
+        ValueStale,
+        WrongFallback,
+        NotSubscribable,
+        WrongSubscriptionAsset,
+        WrongSubscriptionPeriods,
+        NotSubscribed,
+        WrongSourceMode,
     }
 }
 
@@ -94,6 +282,14 @@ impl<T: Trait> From<InternalError> for Error<T> {
             InternalError::WrongValueId(_asset) => Error::<T>::WrongValueId,
             InternalError::UncalculatedValue(_asset) => Error::<T>::NotCalculatedValue,
             InternalError::SourcePermissionDenied => Error::<T>::AccountPermissionDenied,
+            InternalError::NotFrozenValue(_asset) => Error::<T>::ValueNotFrozen,
+            InternalError::NotPastPeriod => Error::<T>::NotCalculateTime,
+            InternalError::PeriodDataNotFound => Error::<T>::NoPeriodData,
+            InternalError::WrongHistoryDepth(_depth) => Error::<T>::WrongHistoryDepth,
+            InternalError::WrongQuorum => Error::<T>::WrongQuorum,
+            InternalError::NoMajority => Error::<T>::NoMajority,
+            InternalError::PayloadValue(_value) => Error::<T>::NotPayloadValue,
+            InternalError::WrongObservationTime => Error::<T>::WrongObservationTime,
             InternalError::CalculationError => Error::<T>::NoneValue,
         }
     }
@@ -105,36 +301,90 @@ decl_module! {
 
         fn deposit_event() = default;
 
+        fn on_runtime_upgrade() {
+            migration::migrate::<T>();
+        }
+
         /// Create oracle in runtime
         ///
         ///  * `name` - A raw string for identify oracle
-        ///  * `source_limit` - Lower limit of the number of sources
+        ///  * `source_limit` - Lower limit of the number of sources, at least one
+        ///  * `quorum` - Part of sources, that must push value for calculation.
+        ///  `None` - `source_limit` values are required.
         ///  * `period` - Defines oracle work cycle. Period have aggregate and calculate part.
         ///  * `aggregate_period` - Part of period when sources can push values. The rest part of
         ///  period - `calculate_part` when we can calculate from pushed values.
         ///  * `asset_id` - Asset with the help of which voting is carried out in tablescore
         ///  * `values_names` - Names of all external values for oracle
+        ///  * `values_decimals` - Count of decimal digits for each value, the value is
+        ///  `value / 10^decimals`
+        ///  * `clock` - Clock for periods: timestamp moments or block numbers
+        ///  * `alignment` - Alignment of period starts: creation moment, multiple of period or anchor
         ///
         pub fn create_oracle(origin,
             name: Vec<u8>,
             source_limit: u8,
+            quorum: Option<Perbill>,
             period: Moment<T>,
             aggregate_period: Moment<T>,
             asset_id: AssetId<T>,
             values_names: Vec<Vec<u8>>,
+            values_decimals: Vec<u8>,
+            clock: ClockSource,
+            alignment: PeriodAlignment<Moment<T>>,
         ) -> dispatch::DispatchResult
         {
             let who = ensure_signed(origin)?;
-            let now = timestamp::Module::<T>::get();
-            let period = PeriodHandler::new(now, period, aggregate_period)
-                .map_err(|_| Error::<T>::WrongPeriods)?;
+            Self::create(
+                who,
+                name,
+                source_limit,
+                quorum,
+                period,
+                aggregate_period,
+                asset_id,
+                values_names,
+                values_decimals,
+                clock,
+                alignment,
+            )?;
+
+            Ok(())
+        }
+
+        /// Set owner of oracle, e.g. of oracle migrated from storage without owners
+        ///
+        /// Can be called only by root.
+        pub fn set_oracle_owner(origin,
+            oracle_id: T::OracleId,
+            owner: T::AccountId) -> dispatch::DispatchResult
+        {
+            ensure_root(origin)?;
+            ensure!(Self::is_oracle_exists(oracle_id), Error::<T>::WrongOracleId);
+
+            OracleOwners::<T>::insert(oracle_id, owner);
 
-            let table = tablescore::Module::<T>::create(who.clone(), asset_id, source_limit, Some(name.clone()))?;
+            Ok(())
+        }
 
-            let id = Self::get_next_oracle_id()?;
-            Oracles::<T>::insert(id, Oracle::<T>::new(name, table, period, source_limit, values_names));
+        /// Set fixed sources of oracle, that aren't updated from tablescore
+        ///
+        /// `None` - sources are selected by voting in tablescore from the next update.
+        /// Can be called only by root.
+        pub fn set_fixed_sources(origin,
+            oracle_id: T::OracleId,
+            sources: Option<Vec<T::AccountId>>) -> dispatch::DispatchResult
+        {
+            ensure_root(origin)?;
+            ensure!(Self::is_oracle_exists(oracle_id), Error::<T>::WrongOracleId);
 
-            Self::deposit_event(RawEvent::OracleCreated(id, who));
+            Oracles::<T>::mutate(oracle_id, |oracle| match sources {
+                Some(sources) => oracle.set_fixed_sources(sources),
+                None => {
+                    oracle.set_tablescore_sources();
+                    Ok(())
+                }
+            }).map_err(Error::<T>::from)?;
 
             Ok(())
         }
@@ -150,10 +400,55 @@ decl_module! {
             values: Vec<T::ValueType>) -> dispatch::DispatchResult
         {
             let who = ensure_signed(origin)?;
-            let now = timestamp::Module::<T>::get();
+            Self::push_observed_values(who, oracle_id, values, None)
+        }
+
+        /// Push values observed off-chain at `observed` moment by oracle clock
+        ///
+        /// `observed` must be within observation tolerance of oracle from current moment.
+        /// Calculated value records median observation moment of pushed values.
+        pub fn push_observed(origin,
+            oracle_id: T::OracleId,
+            values: Vec<T::ValueType>,
+            observed: Moment<T>) -> dispatch::DispatchResult
+        {
+            let who = ensure_signed(origin)?;
+            Self::push_observed_values(who, oracle_id, values, Some(observed))
+        }
+
+        /// Set max difference between observation moment of pushed values and moment of push
+        pub fn set_observation_tolerance(origin,
+            oracle_id: T::OracleId,
+            tolerance: Moment<T>) -> dispatch::DispatchResult
+        {
+            Self::ensure_oracle_owner(origin, oracle_id)?;
+
+            Oracles::<T>::mutate(oracle_id, |oracle| oracle.set_observation_tolerance(tolerance));
+
+            Ok(())
+        }
+
+        /// Push byte payload of value with `Hash` aggregation
+        ///
+        /// Payload is finalised, when threshold part of sources push payloads with
+        /// the same hash in period. Payload can't be longer than `MAX_PAYLOAD_LEN` bytes.
+        pub fn push_payload(origin,
+            oracle_id: T::OracleId,
+            value_id: u8,
+            payload: Vec<u8>) -> dispatch::DispatchResult
+        {
+            let who = ensure_signed(origin)?;
+            ensure!(payload.len() <= MAX_PAYLOAD_LEN, Error::<T>::WrongPayloadLength);
+            ensure!(!Self::is_halted(), Error::<T>::PalletHalted);
+            ensure!(Self::is_oracle_exists(oracle_id), Error::<T>::WrongOracleId);
 
             let oracle = Oracles::<T>::get(oracle_id);
+            ensure!(!oracle.is_paused(), Error::<T>::OraclePaused);
+            ensure!(Self::is_bonded(oracle_id, &who), Error::<T>::NoBond);
+            let now = Self::oracle_now(&oracle);
 
+            // Payloads of the past period are needed to track misses of its sources
+            Self::archive_pushed_data(oracle_id, now);
             if oracle.is_sources_empty()
                 || oracle.period_handler.is_sources_update_needed(now)
             {
@@ -161,19 +456,26 @@ decl_module! {
                     .map_err(Error::<T>::from)?;
             }
 
-            if !oracle.period_handler.is_allow_aggregate(now)
-            {
-                return Err(Error::<T>::NotAggregationTime.into());
-            }
+            ensure!(oracle.period_handler.is_allow_aggregate(now), Error::<T>::NotAggregationTime);
 
-            Oracles::<T>::mutate(oracle_id, |oracle| {
-                oracle.push_values(
-                    &who,
-                    now,
-                    values.into_iter(),
-                )
-            })
-            .map_err(Error::<T>::from)?;
+            let oracle = Oracles::<T>::get(oracle_id);
+            oracle.is_value_id_correct(value_id as usize).map_err(Error::<T>::from)?;
+            let threshold = match oracle.get_aggregation(value_id as usize) {
+                Aggregation::Hash(threshold) => threshold,
+                _ => return Err(Error::<T>::NotPayloadValue.into()),
+            };
+            ensure!(oracle.is_source(&who), Error::<T>::AccountPermissionDenied);
+
+            let hash = T::Hashing::hash(&payload);
+            let period = oracle.period_handler.get_period_number(now);
+            let required = oracle.get_required_sources(threshold);
+
+            let finalized = PayloadValues::<T>::mutate(oracle_id, value_id, |value| {
+                value.push(who, hash, payload, period, now, required)
+            });
+            if let Some(hash) = finalized {
+                Self::deposit_event(RawEvent::PayloadFinalized(oracle_id, value_id, hash));
+            }
 
             Ok(())
         }
@@ -189,80 +491,1451 @@ decl_module! {
             value_id: u8) -> dispatch::DispatchResult
         {
             ensure_signed(origin)?;
-            let now = timestamp::Module::<T>::get();
+            ensure!(!Self::is_halted(), Error::<T>::PalletHalted);
+            ensure!(Self::is_oracle_exists(oracle_id), Error::<T>::WrongOracleId);
             let oracle = Oracles::<T>::get(oracle_id);
+            ensure!(!oracle.is_paused(), Error::<T>::OraclePaused);
+            let now = Self::oracle_now(&oracle);
 
+            Self::archive_pushed_data(oracle_id, now);
             if oracle.period_handler.is_sources_update_needed(now)
             {
                 Self::update_accounts(oracle_id).map_err(Error::<T>::from)?;
             }
 
-            if !oracle.is_allow_calculate(value_id as usize, now).map_err(Error::<T>::from)?
-            {
-                return Err(Error::<T>::NotCalculateTime.into());
-            }
+            let trigger = oracle.get_calculation_trigger(value_id as usize, now)
+                .map_err(Error::<T>::from)?
+                .ok_or(Error::<T>::NotCalculateTime)?;
 
-            let new_value = Oracles::<T>::mutate(oracle_id, |oracle| {
-                oracle.calculate_value(value_id as usize, now)
+            let (new_value, deviations, is_frozen, data_period) = Oracles::<T>::mutate(oracle_id, |oracle| -> Result<_, InternalError> {
+                let voted = oracle.get_source_values(value_id as usize, trigger, now)?;
+                let stakes = Self::get_stakes(oracle_id, oracle, value_id as usize, voted.iter().map(|(source, _pushed)| source));
+                let new_value = oracle.calculate_staked_value(value_id as usize, now, stakes.as_ref())?;
+                let deviations = voted
+                    .into_iter()
+                    .map(|(source, pushed)| {
+                        let deviation = pushed.map(|pushed| {
+                            oracle.get_value_deviation(value_id as usize, pushed, new_value)
+                        });
+                        (source, deviation)
+                    })
+                    .collect::<Vec<_>>();
+                let data_period = oracle.get_data_period(trigger, now);
+                Ok((new_value, deviations, oracle.is_frozen(value_id as usize), data_period))
             }).map_err(Error::<T>::from)?;
 
-            Self::deposit_event(RawEvent::OracleUpdated(oracle_id, value_id, new_value));
+            if !is_frozen {
+                Self::deposit_event(RawEvent::OracleUpdated(oracle_id, value_id, new_value));
+                T::OnOracleUpdate::on_oracle_update(oracle_id, value_id, new_value, now);
+            }
+            if is_frozen && !oracle.is_frozen(value_id as usize) {
+                Self::deposit_event(RawEvent::CircuitBreakerTripped(oracle_id, value_id, new_value));
+            }
+            if trigger == CalculationTrigger::Deviation {
+                Self::deposit_event(RawEvent::DeviationUpdate(oracle_id, value_id, new_value));
+            }
+
+            if Self::rewarded_period(oracle_id, value_id) != Some(data_period) {
+                RewardedPeriods::<T>::insert(oracle_id, value_id, data_period);
+                Self::reward_sources(oracle_id, oracle.get_values_count(), &deviations);
+                Self::track_penalties(oracle_id, &deviations);
+                Self::track_statistics(oracle_id, &deviations);
+            }
+            Self::share_fees(oracle_id, data_period, value_id, oracle.get_values_count(), &deviations);
+
+            Self::check_oracle_heartbeats(oracle_id);
 
             Ok(())
         }
-    }
-}
 
-impl<T: Trait> Module<T> {
-    fn get_next_oracle_id() -> Result<T::OracleId, Error<T>> {
-        OracleIdSequence::<T>::mutate(|id| match id.checked_add(&One::one()) {
-            Some(res) => {
-                let result = *id;
-                *id = res;
-                Ok(result)
+        /// Change `period` and `aggregate_part` of oracle from the next period boundary
+        ///
+        /// Period numbers continue through the change. Repeated call before the boundary
+        /// replaces the scheduled change.
+        pub fn schedule_period_change(origin,
+            oracle_id: T::OracleId,
+            period: Moment<T>,
+            aggregate_period: Moment<T>) -> dispatch::DispatchResult
+        {
+            Self::ensure_oracle_owner(origin, oracle_id)?;
+
+            let begin = Oracles::<T>::mutate(oracle_id, |oracle| {
+                let now = Self::oracle_now(oracle);
+                oracle.period_handler.schedule_change(now, period, aggregate_period)
+            })
+            .map_err(|_| Error::<T>::WrongPeriods)?;
+
+            Self::deposit_event(RawEvent::PeriodChangeScheduled(oracle_id, begin));
+
+            Ok(())
+        }
+
+        /// Stop accepting pushes and calculations in oracle
+        ///
+        /// Calculated values stay readable. Can be called by oracle owner or root.
+        pub fn pause_oracle(origin, oracle_id: T::OracleId) -> dispatch::DispatchResult
+        {
+            Self::ensure_oracle_owner_or_root(origin, oracle_id)?;
+
+            Oracles::<T>::mutate(oracle_id, |oracle| -> Result<_, Error<T>> {
+                ensure!(!oracle.is_paused(), Error::<T>::OraclePaused);
+                oracle.pause();
+                Ok(())
+            })?;
+
+            Self::deposit_event(RawEvent::OraclePaused(oracle_id));
+
+            Ok(())
+        }
+
+        /// Resume paused oracle
+        ///
+        /// New period starts at the moment of resume, data pushed before pause is dropped.
+        /// Can be called by oracle owner or root.
+        pub fn resume_oracle(origin, oracle_id: T::OracleId) -> dispatch::DispatchResult
+        {
+            Self::ensure_oracle_owner_or_root(origin, oracle_id)?;
+
+            let now = Oracles::<T>::mutate(oracle_id, |oracle| -> Result<_, Error<T>> {
+                ensure!(oracle.is_paused(), Error::<T>::OracleNotPaused);
+                let now = Self::oracle_now(oracle);
+                oracle.resume(now);
+                Ok(now)
+            })?;
+            Self::truncate_history(oracle_id, 0);
+
+            Self::deposit_event(RawEvent::OracleResumed(oracle_id, now));
+
+            Ok(())
+        }
+
+        /// Calculate value for past period, that was missed
+        ///
+        /// Pushed data is stored for the number of past periods set by `set_history_depth`.
+        /// Result is available in `past_values` storage.
+        pub fn calculate_past(origin,
+            oracle_id: T::OracleId,
+            value_id: u8,
+            period: Moment<T>) -> dispatch::DispatchResult
+        {
+            ensure_signed(origin)?;
+            ensure!(!Self::is_halted(), Error::<T>::PalletHalted);
+            ensure!(Self::is_oracle_exists(oracle_id), Error::<T>::WrongOracleId);
+            let oracle = Oracles::<T>::get(oracle_id);
+            ensure!(!oracle.is_paused(), Error::<T>::OraclePaused);
+            let now = Self::oracle_now(&oracle);
+
+            Self::archive_pushed_data(oracle_id, now);
+            let value = History::<T>::mutate(oracle_id, period, |data| {
+                let voted = data.iter().flat_map(|data| data.keys());
+                let stakes = Self::get_stakes(oracle_id, &oracle, value_id as usize, voted);
+                oracle.calculate_past_value(value_id as usize, period, data.as_mut(), stakes.as_ref(), now)
+            })
+            .map_err(Error::<T>::from)?;
+
+            PastValues::<T>::insert(oracle_id, (value_id, period), value);
+            Self::deposit_event(RawEvent::PastValueCalculated(oracle_id, value_id, period, value));
+
+            Ok(())
+        }
+
+        /// Keep pushed data for `depth` past periods for late calculations
+        ///
+        /// `depth` must be from 1 to `MAX_HISTORY_DEPTH`
+        pub fn set_history_depth(origin,
+            oracle_id: T::OracleId,
+            depth: u32) -> dispatch::DispatchResult
+        {
+            Self::ensure_oracle_owner(origin, oracle_id)?;
+
+            Oracles::<T>::mutate(oracle_id, |oracle| oracle.set_history_depth(depth))
+                .map_err(Error::<T>::from)?;
+            Self::truncate_history(oracle_id, depth);
+
+            Ok(())
+        }
+
+        /// Stop (`true`) or allow (`false`) pushes and calculations in all oracles
+        ///
+        /// Can be called only by root.
+        pub fn set_halted(origin, is_halted: bool) -> dispatch::DispatchResult
+        {
+            ensure_root(origin)?;
+
+            IsHalted::put(is_halted);
+            Self::deposit_event(RawEvent::HaltedSet(is_halted));
+
+            Ok(())
+        }
+
+        /// Freeze value, when calculated value deviates from the previous one more than `threshold`
+        ///
+        /// Frozen value is held separately until `confirm_value`, consumers read the previous
+        /// value meanwhile. Change of `threshold` keeps the frozen value, `None` - remove circuit
+        /// breaker and drop the frozen value.
+        pub fn set_circuit_breaker(origin,
+            oracle_id: T::OracleId,
+            value_id: u8,
+            threshold: Option<Perbill>) -> dispatch::DispatchResult
+        {
+            Self::ensure_oracle_owner(origin, oracle_id)?;
+
+            Oracles::<T>::mutate(oracle_id, |oracle| {
+                oracle.set_circuit_breaker(value_id as usize, threshold)
+            })
+            .map_err(Error::<T>::from)?;
+
+            Ok(())
+        }
+
+        /// Set aggregation of value pushed by sources
+        ///
+        /// `Majority(threshold)` - value is a category (e.g. 0 - false, 1 - true) pushed by
+        /// sources with the largest bond, that is at least `threshold` part of bonds of
+        /// all pushed votes. Votes are equal in oracle without bond asset.
+        pub fn set_aggregation(origin,
+            oracle_id: T::OracleId,
+            value_id: u8,
+            aggregation: Aggregation) -> dispatch::DispatchResult
+        {
+            Self::ensure_oracle_owner(origin, oracle_id)?;
+
+            Oracles::<T>::mutate(oracle_id, |oracle| {
+                oracle.set_aggregation(value_id as usize, aggregation)
+            })
+            .map_err(Error::<T>::from)?;
+
+            Ok(())
+        }
+
+        /// Add value calculated on read by `formula` over values of any oracles
+        ///
+        /// Derived value has `decimals` decimal digits, its freshness is the oldest
+        /// `last_changed` of formula inputs. Inputs must use clock of the oracle and can't be
+        /// values of permissioned oracles of another owner. Formula can't have more than
+        /// `MAX_BASKET_LEN` inputs and oracle can't have more than `MAX_DERIVED_VALUES`
+        /// derived values.
+        pub fn add_derived_value(origin,
+            oracle_id: T::OracleId,
+            name: Vec<u8>,
+            decimals: u8,
+            formula: Formula<T::OracleId>) -> dispatch::DispatchResult
+        {
+            let who = Self::ensure_oracle_owner(origin, oracle_id)?;
+            ensure!(
+                Self::derived_values(oracle_id).len() < MAX_DERIVED_VALUES,
+                Error::<T>::TooManyDerivedValues
+            );
+
+            let inputs = formula.get_inputs();
+            ensure!(!inputs.is_empty() && inputs.len() <= MAX_BASKET_LEN, Error::<T>::WrongFormula);
+            let clock = Oracles::<T>::get(oracle_id).period_handler.get_clock();
+            inputs.iter().try_for_each(|input| -> dispatch::DispatchResult {
+                ensure!(Self::is_oracle_exists(input.oracle_id), Error::<T>::WrongOracleId);
+                let oracle = Oracles::<T>::get(input.oracle_id);
+                oracle.is_value_id_correct(input.value_id as usize)
+                    .map_err(Error::<T>::from)?;
+                ensure!(oracle.period_handler.get_clock() == clock, Error::<T>::WrongInputClock);
+                ensure!(Self::is_referable(input.oracle_id, &who), Error::<T>::NotOracleOwner);
+                Ok(())
+            })?;
+
+            let derived_id = Self::get_next_derived_id(oracle_id)?;
+            DerivedValues::<T>::mutate(oracle_id, |values| {
+                values.insert(derived_id, DerivedValue { name, decimals, formula })
+            });
+            Self::deposit_event(RawEvent::DerivedValueAdded(oracle_id, derived_id));
+
+            Ok(())
+        }
+
+        /// Remove derived value, ids of the other derived values are kept
+        pub fn remove_derived_value(origin,
+            oracle_id: T::OracleId,
+            derived_id: u8) -> dispatch::DispatchResult
+        {
+            Self::ensure_oracle_owner(origin, oracle_id)?;
+
+            DerivedValues::<T>::mutate(oracle_id, |values| {
+                values.remove(&derived_id).ok_or(Error::<T>::WrongValueId)
+            })?;
+            Self::deposit_event(RawEvent::DerivedValueRemoved(oracle_id, derived_id));
+
+            Ok(())
+        }
+
+        /// Serve consumers with `fallback` value of another oracle, when value is older than
+        /// its max age, degraded by heartbeat, frozen by circuit breaker or not calculated
+        ///
+        /// Fallback chain can't be longer than `MAX_FALLBACK_DEPTH` or contain loops. Fallback
+        /// can't be value of permissioned oracle of another owner. `None` - remove fallback.
+        pub fn set_fallback(origin,
+            oracle_id: T::OracleId,
+            value_id: u8,
+            fallback: Option<ValueRef<T::OracleId>>) -> dispatch::DispatchResult
+        {
+            let who = Self::ensure_oracle_owner(origin, oracle_id)?;
+            Oracles::<T>::get(oracle_id)
+                .is_value_id_correct(value_id as usize)
+                .map_err(Error::<T>::from)?;
+
+            if let Some(fallback) = fallback {
+                ensure!(Self::is_oracle_exists(fallback.oracle_id), Error::<T>::WrongOracleId);
+                Oracles::<T>::get(fallback.oracle_id)
+                    .is_value_id_correct(fallback.value_id as usize)
+                    .map_err(Error::<T>::from)?;
+                ensure!(Self::is_referable(fallback.oracle_id, &who), Error::<T>::NotOracleOwner);
+
+                let primary = ValueRef { oracle_id, value_id };
+                let chain = rstd::iter::successors(Some(fallback), |value| {
+                    Self::fallback(value.oracle_id, value.value_id)
+                });
+                let mut depth = 0;
+                for value in chain {
+                    depth += 1;
+                    ensure!(value != primary && depth <= MAX_FALLBACK_DEPTH, Error::<T>::WrongFallback);
+                }
             }
-            None => Err(Error::<T>::OracleIdOverflow),
-        })
-    }
 
-    fn update_accounts(oracle_id: T::OracleId) -> Result<Vec<AccountId<T>>, InternalError> {
-        Oracles::<T>::mutate(oracle_id, |oracle| {
-            let table = tablescore::Module::<T>::tables(oracle.get_table());
-            let accounts = oracle.update_sources(table.get_head().into_iter().cloned())?;
+            Fallbacks::<T>::mutate(oracle_id, value_id, |value| *value = fallback);
+            Self::deposit_event(RawEvent::FallbackSet(oracle_id, value_id));
 
-            Ok(accounts.into_iter().cloned().collect())
-        })
-    }
+            Ok(())
+        }
 
-    /// Getter for calculate value in oracle
-    fn get_external_value(
-        oracle_id: T::OracleId,
-        value_id: usize,
-    ) -> Result<(T::ValueType, Moment<T>), Error<T>> {
-        Oracles::<T>::get(oracle_id)
-            .values
-            .get(value_id)
-            .ok_or(Error::<T>::WrongValueId)?
-            .get()
-            .ok_or(Error::<T>::NotCalculatedValue)
-    }
+        /// Max age of value served to consumers, older value is served by its fallback
+        ///
+        /// Age is counted from `last_changed` by oracle clock. `None` - value is served at any age.
+        pub fn set_max_age(origin,
+            oracle_id: T::OracleId,
+            value_id: u8,
+            max_age: Option<Moment<T>>) -> dispatch::DispatchResult
+        {
+            Self::ensure_oracle_owner(origin, oracle_id)?;
+            Oracles::<T>::get(oracle_id)
+                .is_value_id_correct(value_id as usize)
+                .map_err(Error::<T>::from)?;
 
-    fn get_or_calculate_external_value(
-        origin: T::Origin,
-        oracle_id: T::OracleId,
-        value_id: usize,
-    ) -> Result<(T::ValueType, Moment<T>), dispatch::DispatchError> {
-        match Oracles::<T>::get(oracle_id)
-            .values
-            .get(value_id)
-            .ok_or(Error::<T>::WrongValueId)?
-            .get()
+            MaxAges::<T>::mutate(oracle_id, value_id, |value| *value = max_age);
+            Self::deposit_event(RawEvent::MaxAgeSet(oracle_id, value_id));
+
+            Ok(())
+        }
+
+        /// Confirm value frozen by circuit breaker, it replaces the previous value for consumers
+        ///
+        /// Updates of values are announced on confirmation, not while they are frozen.
+        pub fn confirm_value(origin,
+            oracle_id: T::OracleId,
+            value_id: u8) -> dispatch::DispatchResult
+        {
+            Self::ensure_oracle_owner(origin, oracle_id)?;
+
+            Oracles::<T>::mutate(oracle_id, |oracle| oracle.confirm_value(value_id as usize))
+                .map_err(Error::<T>::from)?;
+
+            Self::deposit_event(RawEvent::ValueConfirmed(oracle_id, value_id));
+            if let Ok((value, last_changed)) = Self::get_external_value(oracle_id, value_id as usize) {
+                Self::deposit_event(RawEvent::OracleUpdated(oracle_id, value_id, value));
+                T::OnOracleUpdate::on_oracle_update(oracle_id, value_id, value, last_changed);
+            }
+
+            Ok(())
+        }
+
+        /// Require calculation of value at least every `periods` periods
+        ///
+        /// If value was not calculated in time, it is marked as degraded until next calculation.
+        /// `None` - remove requirement.
+        pub fn set_heartbeat(origin,
+            oracle_id: T::OracleId,
+            value_id: u8,
+            periods: Option<u32>) -> dispatch::DispatchResult
+        {
+            Self::ensure_oracle_owner(origin, oracle_id)?;
+
+            Oracles::<T>::mutate(oracle_id, |oracle| {
+                let now = Self::oracle_now(oracle);
+                oracle.set_heartbeat(value_id as usize, periods, now)
+            })
+            .map_err(Error::<T>::from)?;
+
+            Ok(())
+        }
+
+        /// Mark values of oracle, which were not calculated in time, as degraded
+        pub fn check_heartbeats(origin,
+            oracle_id: T::OracleId) -> dispatch::DispatchResult
         {
-            Some((value, moment)) => Ok((value, moment)),
-            None => {
+            ensure_signed(origin)?;
+            ensure!(Self::is_oracle_exists(oracle_id), Error::<T>::WrongOracleId);
+
+            Self::check_oracle_heartbeats(oracle_id);
+
+            Ok(())
+        }
+
+        /// Require `quorum` part of current sources to push value for calculation
+        ///
+        /// `None` - `source_limit` values are required.
+        pub fn set_quorum(origin,
+            oracle_id: T::OracleId,
+            quorum: Option<Perbill>) -> dispatch::DispatchResult
+        {
+            Self::ensure_oracle_owner(origin, oracle_id)?;
+
+            Oracles::<T>::mutate(oracle_id, |oracle| oracle.set_quorum(quorum))
+                .map_err(Error::<T>::from)?;
+
+            Ok(())
+        }
+
+        /// Set deviation threshold of oracle
+        ///
+        /// When median of values pushed in current aggregate part deviates from the last
+        /// calculated value more than `threshold`, the value can be calculated immediately.
+        /// `None` - calculate only in calculate part.
+        pub fn set_deviation_threshold(origin,
+            oracle_id: T::OracleId,
+            threshold: Option<Perbill>) -> dispatch::DispatchResult
+        {
+            Self::ensure_oracle_owner(origin, oracle_id)?;
+
+            Oracles::<T>::mutate(oracle_id, |oracle| oracle.set_deviation_threshold(threshold));
+
+            Ok(())
+        }
+
+        /// Set reward pool for sources of oracle
+        ///
+        /// Each period `period_reward` is distributed among sources which pushed values within
+        /// `tolerance` of calculated ones. Sources are rewarded once per period for each value.
+        /// Asset of pool with funds or unclaimed rewards can't be changed.
+        pub fn set_reward_pool(origin,
+            oracle_id: T::OracleId,
+            asset_id: AssetId<T>,
+            period_reward: Balance<T>,
+            tolerance: Perbill) -> dispatch::DispatchResult
+        {
+            Self::ensure_oracle_owner(origin, oracle_id)?;
+
+            let pool = match Self::reward_pool(oracle_id) {
+                Some(pool) if pool.asset_id != asset_id && !pool.is_empty() => {
+                    return Err(Error::<T>::RewardPoolNotEmpty.into());
+                }
+                Some(pool) => RewardPool {
+                    asset_id,
+                    period_reward,
+                    tolerance,
+                    ..pool
+                },
+                None => RewardPool::new(asset_id, period_reward, tolerance),
+            };
+            RewardPools::<T>::insert(oracle_id, pool);
+
+            Ok(())
+        }
+
+        /// Transfer `amount` of pool asset to oracle reward pool
+        pub fn fund_reward_pool(origin,
+            oracle_id: T::OracleId,
+            amount: Balance<T>) -> dispatch::DispatchResult
+        {
+            let who = ensure_signed(origin)?;
+            let pool = Self::reward_pool(oracle_id).ok_or(Error::<T>::NoRewardPool)?;
+
+            assets::Module::<T>::make_transfer_with_event(&pool.asset_id, &who, &Self::account_id(oracle_id), amount)?;
+            RewardPools::<T>::mutate(oracle_id, |pool| {
+                if let Some(pool) = pool {
+                    pool.balance = pool.balance.saturating_add(amount);
+                }
+            });
+
+            Self::deposit_event(RawEvent::RewardPoolFunded(oracle_id, who, amount));
+
+            Ok(())
+        }
+
+        /// Claim all rewards of source in oracle
+        pub fn claim_reward(origin,
+            oracle_id: T::OracleId) -> dispatch::DispatchResult
+        {
+            let who = ensure_signed(origin)?;
+            let pool = Self::reward_pool(oracle_id).ok_or(Error::<T>::NoRewardPool)?;
+
+            let reward = Self::rewards(oracle_id, &who);
+            if reward.is_zero() {
+                return Err(Error::<T>::NothingToClaim.into());
+            }
+
+            assets::Module::<T>::make_transfer_with_event(&pool.asset_id, &Self::account_id(oracle_id), &who, reward)?;
+            Rewards::<T>::remove(oracle_id, &who);
+            RewardPools::<T>::mutate(oracle_id, |pool| {
+                if let Some(pool) = pool {
+                    pool.claim(reward);
+                }
+            });
+
+            Self::deposit_event(RawEvent::RewardClaimed(oracle_id, who, reward));
+
+            Ok(())
+        }
+
+        /// Make oracle permissioned, consumers pay `period_fee` of asset to read it
+        ///
+        /// Fees are shared among sources, whose values were used in calculations of the paid
+        /// period. Fees, that weren't shared in their period, are carried over to the next
+        /// period with calculations. Asset of subscriptions can't be changed.
+        pub fn set_subscription_fee(origin,
+            oracle_id: T::OracleId,
+            asset_id: AssetId<T>,
+            period_fee: Balance<T>) -> dispatch::DispatchResult
+        {
+            Self::ensure_oracle_owner(origin, oracle_id)?;
+
+            if let Some(config) = Self::subscription_config(oracle_id) {
+                ensure!(config.asset_id == asset_id, Error::<T>::WrongSubscriptionAsset);
+            }
+            SubscriptionConfigs::<T>::insert(oracle_id, SubscriptionConfig { asset_id, period_fee });
+
+            Ok(())
+        }
+
+        /// Pay fees of permissioned oracle for `periods` from current period or from the end
+        /// of active subscription
+        pub fn subscribe(origin,
+            oracle_id: T::OracleId,
+            periods: u32) -> dispatch::DispatchResult
+        {
+            let who = ensure_signed(origin)?;
+            let config = Self::subscription_config(oracle_id).ok_or(Error::<T>::NotSubscribable)?;
+            ensure!(
+                periods > 0 && periods <= MAX_SUBSCRIPTION_PERIODS,
+                Error::<T>::WrongSubscriptionPeriods
+            );
+
+            let oracle = Oracles::<T>::get(oracle_id);
+            let period = oracle.period_handler.get_period_number(Self::oracle_now(&oracle));
+            let (subscription, first) = Subscription::extend(Self::subscription(oracle_id, &who), period, periods);
+
+            let amount = config.period_fee.saturating_mul(Balance::<T>::from(periods));
+            assets::Module::<T>::make_transfer_with_event(&config.asset_id, &who, &Self::account_id(oracle_id), amount)?;
+
+            (0..periods).for_each(|index| {
+                let paid = first.saturating_add(Moment::<T>::from(index));
+                Self::add_period_fees(oracle_id, paid, config.period_fee);
+            });
+            let end = subscription.end;
+            Subscriptions::<T>::insert(oracle_id, &who, subscription);
+
+            Self::deposit_event(RawEvent::Subscribed(oracle_id, who, amount, end));
+
+            Ok(())
+        }
+
+        /// Claim all subscription fees of source in oracle
+        pub fn claim_fees(origin,
+            oracle_id: T::OracleId) -> dispatch::DispatchResult
+        {
+            let who = ensure_signed(origin)?;
+            let config = Self::subscription_config(oracle_id).ok_or(Error::<T>::NotSubscribable)?;
+
+            let fees = Self::fees(oracle_id, &who);
+            if fees.is_zero() {
+                return Err(Error::<T>::NothingToClaim.into());
+            }
+
+            assets::Module::<T>::make_transfer_with_event(&config.asset_id, &Self::account_id(oracle_id), &who, fees)?;
+            Fees::<T>::remove(oracle_id, &who);
+
+            Self::deposit_event(RawEvent::FeesClaimed(oracle_id, who, fees));
+
+            Ok(())
+        }
+
+        /// Set penalty config of oracle and enable penalty tracking for its sources
+        ///
+        ///  * `tolerance` - Max deviation of pushed value from calculated one
+        ///  * `max_misses` - Count of periods without pushes, after which source is penalized
+        ///  * `max_deviations` - Count of values out of tolerance, after which source is penalized
+        ///  * `slash_amount` - Amount slashed from source bond in penalty
+        pub fn set_penalty_config(origin,
+            oracle_id: T::OracleId,
+            tolerance: Perbill,
+            max_misses: u32,
+            max_deviations: u32,
+            slash_amount: Balance<T>) -> dispatch::DispatchResult
+        {
+            Self::ensure_oracle_owner(origin, oracle_id)?;
+
+            PenaltyConfigs::<T>::insert(oracle_id, PenaltyConfig {
+                tolerance,
+                max_misses,
+                max_deviations,
+                slash_amount,
+            });
+
+            Ok(())
+        }
+
+        /// Require sources of oracle to bond `asset_id` for pushes
+        ///
+        /// Bonds are slashed in penalties and weight votes in majority aggregation. Asset of
+        /// bonds can't be changed.
+        pub fn set_bond_asset(origin,
+            oracle_id: T::OracleId,
+            asset_id: AssetId<T>) -> dispatch::DispatchResult
+        {
+            Self::ensure_oracle_owner(origin, oracle_id)?;
+
+            if let Some(bond_asset) = Self::bond_asset(oracle_id) {
+                ensure!(bond_asset == asset_id, Error::<T>::WrongBondAsset);
+            }
+            BondAssets::<T>::insert(oracle_id, asset_id);
+
+            Ok(())
+        }
+
+        /// Reserve `amount` of oracle bond asset as source bond in oracle
+        pub fn bond(origin,
+            oracle_id: T::OracleId,
+            amount: Balance<T>) -> dispatch::DispatchResult
+        {
+            let who = ensure_signed(origin)?;
+            ensure!(Self::is_oracle_exists(oracle_id), Error::<T>::WrongOracleId);
+            let asset_id = Self::bond_asset(oracle_id).ok_or(Error::<T>::NoBondAsset)?;
+
+            let bond = match Self::bonds(oracle_id, &who) {
+                Some(bond) => Bond {
+                    asset_id,
+                    amount: bond.amount.saturating_add(amount),
+                },
+                None => Bond { asset_id, amount },
+            };
+
+            assets::Module::<T>::reserve(&asset_id, &who, amount)?;
+            Bonds::<T>::insert(oracle_id, &who, bond);
+
+            Self::deposit_event(RawEvent::SourceBonded(oracle_id, who, amount));
+
+            Ok(())
+        }
+
+        /// Unreserve source bond in oracle
+        ///
+        /// The source must not be in current oracle sources
+        pub fn unbond(origin,
+            oracle_id: T::OracleId) -> dispatch::DispatchResult
+        {
+            let who = ensure_signed(origin)?;
+
+            if Oracles::<T>::get(oracle_id).is_source(&who) {
+                return Err(Error::<T>::SourceIsActive.into());
+            }
+
+            let bond = Bonds::<T>::take(oracle_id, &who).ok_or(Error::<T>::NoBond)?;
+            assets::Module::<T>::unreserve(&bond.asset_id, &who, bond.amount);
+
+            Self::deposit_event(RawEvent::SourceUnbonded(oracle_id, who, bond.amount));
+
+            Ok(())
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+    /// Account that holds reward pool and subscription fees of oracle
+    pub fn account_id(oracle_id: T::OracleId) -> T::AccountId {
+        MODULE_ID.into_sub_account(oracle_id)
+    }
+
+    fn ensure_oracle_owner(
+        origin: T::Origin,
+        oracle_id: T::OracleId,
+    ) -> Result<AccountId<T>, dispatch::DispatchError> {
+        let who = ensure_signed(origin)?;
+        if Self::oracle_owner(oracle_id).as_ref() == Some(&who) {
+            Ok(who)
+        } else {
+            Err(Error::<T>::NotOracleOwner.into())
+        }
+    }
+
+    fn ensure_oracle_owner_or_root(
+        origin: T::Origin,
+        oracle_id: T::OracleId,
+    ) -> dispatch::DispatchResult {
+        ensure!(Self::is_oracle_exists(oracle_id), Error::<T>::WrongOracleId);
+        match ensure_root(origin.clone()) {
+            Ok(()) => Ok(()),
+            Err(_) => Self::ensure_oracle_owner(origin, oracle_id).map(|_| ()),
+        }
+    }
+
+    /// Push values of source, observed at `observed` moment or now
+    fn push_observed_values(
+        who: AccountId<T>,
+        oracle_id: T::OracleId,
+        values: Vec<T::ValueType>,
+        observed: Option<Moment<T>>,
+    ) -> dispatch::DispatchResult {
+        ensure!(!Self::is_halted(), Error::<T>::PalletHalted);
+        ensure!(Self::is_oracle_exists(oracle_id), Error::<T>::WrongOracleId);
+
+        let oracle = Oracles::<T>::get(oracle_id);
+        ensure!(!oracle.is_paused(), Error::<T>::OraclePaused);
+        ensure!(Self::is_bonded(oracle_id, &who), Error::<T>::NoBond);
+        let now = Self::oracle_now(&oracle);
+
+        Self::archive_pushed_data(oracle_id, now);
+        if oracle.is_sources_empty() || oracle.period_handler.is_sources_update_needed(now) {
+            Self::update_accounts(oracle_id).map_err(Error::<T>::from)?;
+        }
+
+        if !oracle.period_handler.is_allow_aggregate(now) {
+            return Err(Error::<T>::NotAggregationTime.into());
+        }
+
+        Oracles::<T>::mutate(oracle_id, |oracle| {
+            oracle.push_observed_values(&who, now, observed.unwrap_or(now), values.into_iter())
+        })
+        .map_err(Error::<T>::from)?;
+
+        Self::check_oracle_heartbeats(oracle_id);
+
+        Ok(())
+    }
+
+    fn check_oracle_heartbeats(oracle_id: T::OracleId) {
+        Oracles::<T>::mutate(oracle_id, |oracle| {
+            let now = Self::oracle_now(oracle);
+            oracle.check_heartbeats(now)
+        })
+        .into_iter()
+        .for_each(|value_id| {
+            Self::deposit_event(RawEvent::HeartbeatMissed(oracle_id, value_id as u8))
+        });
+    }
+
+    /// Share value reward from pool between sources, which pushed values close to calculated
+    ///
+    /// `deviations` - deviation of value pushed by each source, `None` if it didn't push
+    fn reward_sources(
+        oracle_id: T::OracleId,
+        values_count: usize,
+        deviations: &[(AccountId<T>, Option<Perbill>)],
+    ) {
+        RewardPools::<T>::mutate(oracle_id, |pool| {
+            if let Some(pool) = pool {
+                let accurate: Vec<&AccountId<T>> = deviations
+                    .iter()
+                    .filter_map(|(source, deviation)| match deviation {
+                        Some(deviation) if *deviation <= pool.tolerance => Some(source),
+                        _ => None,
+                    })
+                    .collect();
+
+                let reward = pool.take_value_reward(values_count, accurate.len());
+                if reward.is_zero() {
+                    return;
+                }
+
+                accurate.into_iter().for_each(|source| {
+                    Rewards::<T>::mutate(oracle_id, source, |balance| {
+                        *balance = balance.saturating_add(reward)
+                    });
+                    Self::deposit_event(RawEvent::SourceRewarded(
+                        oracle_id,
+                        source.clone(),
+                        reward,
+                    ));
+                });
+            }
+        });
+    }
+
+    /// Share subscription fees of period among sources, whose values were used in calculation
+    /// of value with `value_id` from data of the period
+    fn share_fees(
+        oracle_id: T::OracleId,
+        period: Moment<T>,
+        value_id: u8,
+        values_count: usize,
+        deviations: &[(AccountId<T>, Option<Perbill>)],
+    ) {
+        Self::carry_over_fees(oracle_id, period);
+        if Self::subscription_fees(oracle_id, period).left.is_zero() {
+            return;
+        }
+
+        let contributed: Vec<&AccountId<T>> = deviations
+            .iter()
+            .filter(|(_source, deviation)| deviation.is_some())
+            .map(|(source, _deviation)| source)
+            .collect();
+
+        let fee = SubscriptionFees::<T>::mutate(oracle_id, period, |fees| {
+            fees.take_value_fee(value_id, values_count, contributed.len())
+        });
+        if fee.is_zero() {
+            return;
+        }
+
+        contributed.into_iter().for_each(|source| {
+            Fees::<T>::mutate(oracle_id, source, |balance| {
+                *balance = balance.saturating_add(fee)
+            });
+            Self::deposit_event(RawEvent::SubscriptionFeePaid(
+                oracle_id,
+                source.clone(),
+                fee,
+            ));
+        });
+    }
+
+    /// Move fees left in periods before `period` to fees of `period`
+    fn carry_over_fees(oracle_id: T::OracleId, period: Moment<T>) {
+        let past_periods = FeePeriods::<T>::mutate(oracle_id, |periods| {
+            let past = periods.iter().take_while(|past| **past < period).count();
+            periods.drain(..past).collect::<Vec<_>>()
+        });
+
+        let left = past_periods
+            .into_iter()
+            .fold(Zero::zero(), |left: Balance<T>, past| {
+                left.saturating_add(SubscriptionFees::<T>::take(oracle_id, past).left)
+            });
+        if !left.is_zero() {
+            Self::add_period_fees(oracle_id, period, left);
+        }
+    }
+
+    fn add_period_fees(oracle_id: T::OracleId, period: Moment<T>, fee: Balance<T>) {
+        SubscriptionFees::<T>::mutate(oracle_id, period, |fees| fees.add(fee));
+        FeePeriods::<T>::mutate(oracle_id, |periods| {
+            if let Err(index) = periods.binary_search(&period) {
+                periods.insert(index, period);
+            }
+        });
+    }
+
+    /// Move data pushed in past period of oracle to history and track sources, that
+    /// didn't push in it
+    fn archive_pushed_data(oracle_id: T::OracleId, now: Moment<T>) {
+        let archived = Oracles::<T>::mutate(oracle_id, |oracle| {
+            let current_period = oracle.period_handler.get_period_number(now);
+            let payload_ids: Vec<u8> = (0..oracle.get_values_count())
+                .filter(|value_id| {
+                    matches!(oracle.get_aggregation(*value_id), Aggregation::Hash(_))
+                })
+                .map(|value_id| value_id as u8)
+                .collect();
+            oracle
+                .archive_pushed_data(current_period)
+                .map(|archived| (archived, oracle.get_history_depth(), payload_ids))
+        });
+
+        if let Some((archived, depth, payload_ids)) = archived {
+            // Source pushed in period, if it pushed values or payloads
+            let period = archived.period;
+            let pushes: Vec<(AccountId<T>, bool)> = archived
+                .pushes
+                .into_iter()
+                .map(|(source, is_pushed)| {
+                    let is_pushed = is_pushed
+                        || payload_ids.iter().any(|value_id| {
+                            Self::payload_value(oracle_id, *value_id).is_pushed_by(&source, period)
+                        });
+                    (source, is_pushed)
+                })
+                .collect();
+
+            Self::store_history(oracle_id, period, archived.data, depth);
+            Self::track_misses(oracle_id, &pushes);
+        }
+    }
+
+    /// Store data pushed in past period, history is truncated to `depth` periods
+    pub(crate) fn store_history(
+        oracle_id: T::OracleId,
+        period: Moment<T>,
+        data: PeriodData<AccountId<T>, T::ValueType, Moment<T>>,
+        depth: u32,
+    ) {
+        History::<T>::insert(oracle_id, period, data);
+        HistoryPeriods::<T>::mutate(oracle_id, |periods| periods.push(period));
+        Self::truncate_history(oracle_id, depth);
+    }
+
+    /// Remove the oldest stored periods of oracle beyond `depth` periods
+    fn truncate_history(oracle_id: T::OracleId, depth: u32) {
+        HistoryPeriods::<T>::mutate(oracle_id, |periods| {
+            let excess = periods.len().saturating_sub(depth as usize);
+            periods
+                .drain(..excess)
+                .for_each(|period| History::<T>::remove(oracle_id, period));
+        });
+    }
+
+    /// Track sources, that didn't push in archived period, and penalize them on limits
+    /// exceeding
+    ///
+    /// `pushes` - whether each source pushed values or payloads in the period
+    fn track_misses(oracle_id: T::OracleId, pushes: &[(AccountId<T>, bool)]) {
+        let config = Self::penalty_config(oracle_id);
+
+        pushes
+            .iter()
+            .filter(|(_source, is_pushed)| !is_pushed)
+            .for_each(|(source, _is_pushed)| {
+                SourceStatistics::<T>::mutate(oracle_id, source, |stats| stats.track_miss());
+                if let Some(config) = &config {
+                    Self::track_penalty(oracle_id, source, config, SourcePenalty::track_miss);
+                }
+            });
+    }
+
+    /// Track deviations of sources and penalize them on limits exceeding
+    fn track_penalties(oracle_id: T::OracleId, deviations: &[(AccountId<T>, Option<Perbill>)]) {
+        let config = match Self::penalty_config(oracle_id) {
+            Some(config) => config,
+            None => return,
+        };
+
+        deviations.iter().for_each(|(source, deviation)| {
+            if let Some(deviation) = deviation {
+                Self::track_penalty(oracle_id, source, &config, |penalty| {
+                    penalty.track_deviation(*deviation, config.tolerance)
+                });
+            }
+        });
+    }
+
+    /// Apply `track` to penalty of source and penalize it on limits exceeding
+    fn track_penalty(
+        oracle_id: T::OracleId,
+        source: &AccountId<T>,
+        config: &PenaltyConfig<Balance<T>>,
+        track: impl FnOnce(&mut SourcePenalty),
+    ) {
+        let mut penalty = Self::penalties(oracle_id, source);
+        track(&mut penalty);
+
+        if penalty.is_limit_exceeded(config) {
+            Penalties::<T>::remove(oracle_id, source);
+            Self::penalize(oracle_id, source, config.slash_amount);
+        } else {
+            Penalties::<T>::insert(oracle_id, source, penalty);
+        }
+    }
+
+    /// Update statistics of sources by their values in calculation
+    fn track_statistics(oracle_id: T::OracleId, deviations: &[(AccountId<T>, Option<Perbill>)]) {
+        deviations.iter().for_each(|(source, deviation)| {
+            if let Some(deviation) = deviation {
+                SourceStatistics::<T>::mutate(oracle_id, source, |stats| {
+                    stats.track_deviation(*deviation)
+                });
+            }
+        });
+    }
+
+    /// Slash source bond and notify penalty handler
+    fn penalize(oracle_id: T::OracleId, source: &AccountId<T>, amount: Balance<T>) {
+        let slashed = Bonds::<T>::mutate(oracle_id, source, |maybe_bond| {
+            let slashed = match maybe_bond {
+                Some(bond) => {
+                    let amount = rstd::cmp::min(amount, bond.amount);
+                    let not_slashed =
+                        assets::Module::<T>::slash_reserved(&bond.asset_id, source, amount)
+                            .unwrap_or_else(Zero::zero);
+                    let slashed = amount - not_slashed;
+                    bond.amount -= slashed;
+                    slashed
+                }
+                None => Zero::zero(),
+            };
+
+            if matches!(maybe_bond, Some(bond) if bond.amount.is_zero()) {
+                *maybe_bond = None;
+            }
+            slashed
+        });
+
+        T::OnSourcePenalty::on_source_penalty(oracle_id, source);
+        Self::deposit_event(RawEvent::SourcePenalized(
+            oracle_id,
+            source.clone(),
+            slashed,
+        ));
+    }
+
+    /// Source can push to oracle: oracle has no bond asset or source has bond
+    fn is_bonded(oracle_id: T::OracleId, source: &AccountId<T>) -> bool {
+        Self::bond_asset(oracle_id).is_none() || Self::bonds(oracle_id, source).is_some()
+    }
+
+    /// Bonds of voted `sources` as stakes for majority aggregation of value
+    ///
+    /// `None` - votes are equal, when oracle has no bond asset or value isn't aggregated by
+    /// majority
+    fn get_stakes<'a>(
+        oracle_id: T::OracleId,
+        oracle: &Oracle<T>,
+        value_id: usize,
+        sources: impl Iterator<Item = &'a AccountId<T>>,
+    ) -> Option<Stakes<AccountId<T>>> {
+        if !matches!(oracle.get_aggregation(value_id), Aggregation::Majority(_)) {
+            return None;
+        }
+        Self::bond_asset(oracle_id)?;
+
+        Some(
+            sources
+                .map(|source| {
+                    let stake = Self::bonds(oracle_id, source)
+                        .map_or(0, |bond| bond.amount.unique_saturated_into());
+                    (source.clone(), stake)
+                })
+                .collect(),
+        )
+    }
+
+    /// Create oracle with tablescore table of sources
+    #[allow(clippy::too_many_arguments)]
+    fn create(
+        owner: T::AccountId,
+        name: Vec<u8>,
+        source_limit: u8,
+        quorum: Option<Perbill>,
+        period: Moment<T>,
+        aggregate_period: Moment<T>,
+        asset_id: AssetId<T>,
+        values_names: Vec<Vec<u8>>,
+        values_decimals: Vec<u8>,
+        clock: ClockSource,
+        alignment: PeriodAlignment<Moment<T>>,
+    ) -> Result<T::OracleId, dispatch::DispatchError> {
+        ensure!(source_limit > 0, Error::<T>::WrongSourceLimit);
+        ensure!(
+            quorum.map_or(true, |quorum| !quorum.is_zero()),
+            Error::<T>::WrongQuorum
+        );
+        ensure!(
+            values_decimals.len() == values_names.len(),
+            Error::<T>::WrongValuesCount
+        );
+        let now = clock.now::<T>();
+        let period = PeriodHandler::with_alignment(clock, alignment, now, period, aggregate_period)
+            .map_err(|_| Error::<T>::WrongPeriods)?;
+
+        let table = tablescore::Module::<T>::create(
+            owner.clone(),
+            asset_id,
+            source_limit,
+            Some(name.clone()),
+        )?;
+
+        let mut oracle = Oracle::<T>::new(name, table, period, source_limit, values_names);
+        oracle.set_quorum(quorum).map_err(Error::<T>::from)?;
+        oracle
+            .set_decimals(values_decimals)
+            .map_err(Error::<T>::from)?;
+
+        let id = Self::get_next_oracle_id()?;
+        Oracles::<T>::insert(id, oracle);
+        OracleOwners::<T>::insert(id, owner.clone());
+
+        Self::deposit_event(RawEvent::OracleCreated(id, owner));
+
+        Ok(id)
+    }
+
+    /// Create oracle from genesis config with its sources and initial values
+    #[cfg(feature = "std")]
+    fn create_genesis_oracle(
+        genesis: OracleGenesis<T::AccountId, AssetId<T>, Moment<T>, T::ValueType>,
+    ) -> dispatch::DispatchResult {
+        ensure!(
+            genesis.source_mode == SourceMode::Fixed || genesis.sources.is_empty(),
+            Error::<T>::WrongSourceMode
+        );
+
+        let id = Self::create(
+            genesis.owner,
+            genesis.name,
+            genesis.source_limit,
+            genesis.quorum,
+            genesis.period,
+            genesis.aggregate_period,
+            genesis.asset_id,
+            genesis.values_names,
+            genesis.values_decimals,
+            genesis.clock,
+            genesis.alignment,
+        )?;
+
+        let (sources, values) = (genesis.sources, genesis.values);
+        Oracles::<T>::mutate(id, |oracle| -> Result<(), InternalError> {
+            if genesis.source_mode == SourceMode::Fixed {
+                oracle.set_fixed_sources(sources)?;
+            }
+            if !values.is_empty() {
+                let now = Self::oracle_now(oracle);
+                oracle.set_initial_values(values, now)?;
+            }
+            Ok(())
+        })
+        .map_err(Error::<T>::from)?;
+
+        Ok(())
+    }
+
+    fn get_next_oracle_id() -> Result<T::OracleId, Error<T>> {
+        OracleIdSequence::<T>::mutate(|id| match id.checked_add(&One::one()) {
+            Some(res) => {
+                let result = *id;
+                *id = res;
+                Ok(result)
+            }
+            None => Err(Error::<T>::OracleIdOverflow),
+        })
+    }
+
+    fn get_next_derived_id(oracle_id: T::OracleId) -> Result<u8, Error<T>> {
+        DerivedIdSequence::<T>::mutate(oracle_id, |id| match id.checked_add(1) {
+            Some(res) => {
+                let result = *id;
+                *id = res;
+                Ok(result)
+            }
+            None => Err(Error::<T>::DerivedIdOverflow),
+        })
+    }
+
+    fn update_accounts(oracle_id: T::OracleId) -> Result<Vec<AccountId<T>>, InternalError> {
+        Oracles::<T>::mutate(oracle_id, |oracle| {
+            if oracle.get_source_mode() == SourceMode::Fixed {
+                return Ok(oracle.get_sources().cloned().collect());
+            }
+
+            let table = tablescore::Module::<T>::tables(oracle.get_table());
+            let accounts = oracle.update_sources(table.get_head().into_iter().cloned())?;
+
+            Ok(accounts.into_iter().cloned().collect())
+        })
+    }
+
+    /// Current moment by clock of oracle
+    fn oracle_now(oracle: &Oracle<T>) -> Moment<T> {
+        oracle.period_handler.get_clock().now::<T>()
+    }
+
+    fn is_oracle_exists(oracle_id: T::OracleId) -> bool {
+        oracle_id < Self::next_oracle_id()
+    }
+
+    /// Ids of all created oracles
+    pub fn oracle_ids() -> Vec<T::OracleId> {
+        let next = Self::next_oracle_id();
+        rstd::iter::successors(Some(T::OracleId::zero()), |id| Some(*id + One::one()))
+            .take_while(|id| *id < next)
+            .collect()
+    }
+
+    /// Metadata of oracle for read API
+    pub fn oracle_info(oracle_id: T::OracleId) -> Option<OracleInfo<AccountId<T>, Moment<T>>> {
+        if !Self::is_oracle_exists(oracle_id) {
+            return None;
+        }
+
+        let oracle = Oracles::<T>::get(oracle_id);
+        let now = Self::oracle_now(&oracle);
+        Some(OracleInfo {
+            name: oracle.name.clone(),
+            owner: Self::oracle_owner(oracle_id),
+            source_limit: oracle.get_source_limit(),
+            quorum: oracle.get_quorum(),
+            period: oracle.period_handler.get_period(now),
+            aggregate_part: oracle.period_handler.get_aggregate_part(now),
+            clock: oracle.period_handler.get_clock(),
+            source_mode: oracle.get_source_mode(),
+            is_paused: oracle.is_paused(),
+            values_names: oracle.names,
+        })
+    }
+
+    /// All external values of oracle for read API
+    pub fn values_info(oracle_id: T::OracleId) -> Vec<ValueInfo<T::ValueType, Moment<T>>> {
+        if !Self::is_oracle_exists(oracle_id) {
+            return Vec::new();
+        }
+
+        let oracle = Oracles::<T>::get(oracle_id);
+        (0..oracle.get_values_count())
+            .filter_map(|value_id| Self::get_value_info(oracle_id, &oracle, value_id))
+            .collect()
+    }
+
+    /// External value of oracle by id for read API
+    pub fn value_info(
+        oracle_id: T::OracleId,
+        value_id: u8,
+    ) -> Option<ValueInfo<T::ValueType, Moment<T>>> {
+        if !Self::is_oracle_exists(oracle_id) {
+            return None;
+        }
+
+        Self::get_value_info(oracle_id, &Oracles::<T>::get(oracle_id), value_id as usize)
+    }
+
+    /// External value of oracle by name for read API
+    pub fn value_info_by_name(
+        oracle_id: T::OracleId,
+        name: Vec<u8>,
+    ) -> Option<ValueInfo<T::ValueType, Moment<T>>> {
+        if !Self::is_oracle_exists(oracle_id) {
+            return None;
+        }
+
+        let oracle = Oracles::<T>::get(oracle_id);
+        let value_id = oracle
+            .names
+            .iter()
+            .position(|value_name| *value_name == name)?;
+        Self::get_value_info(oracle_id, &oracle, value_id)
+    }
+
+    /// Current period number and part of oracle for read API
+    pub fn period_info(oracle_id: T::OracleId) -> Option<PeriodInfo<Moment<T>>> {
+        if !Self::is_oracle_exists(oracle_id) {
+            return None;
+        }
+
+        let period_handler = Oracles::<T>::get(oracle_id).period_handler;
+        let now = period_handler.get_clock().now::<T>();
+        Some(PeriodInfo {
+            number: period_handler.get_period_number(now),
+            part: period_handler.get_part(now),
+        })
+    }
+
+    /// Sources of oracle, that were selected at last sources update
+    pub fn sources(oracle_id: T::OracleId) -> Vec<AccountId<T>> {
+        Oracles::<T>::get(oracle_id)
+            .get_sources()
+            .cloned()
+            .collect()
+    }
+
+    fn get_value_info(
+        oracle_id: T::OracleId,
+        oracle: &Oracle<T>,
+        value_id: usize,
+    ) -> Option<ValueInfo<T::ValueType, Moment<T>>> {
+        let now = Self::oracle_now(oracle);
+        let external = oracle.values.get(value_id)?;
+        let aggregation = oracle.get_aggregation(value_id);
+
+        let (payload, last_changed) = match aggregation {
+            Aggregation::Hash(_) => {
+                let payload_value = Self::payload_value(oracle_id, value_id as u8);
+                (payload_value.payload, payload_value.last_changed)
+            }
+            _ => (None, external.last_changed),
+        };
+
+        Some(ValueInfo {
+            name: oracle.names[value_id].clone(),
+            value: external.value,
+            payload,
+            decimals: oracle.decimals[value_id],
+            aggregation,
+            last_changed,
+            observed: external.observed,
+            dispersion: oracle.get_dispersion(value_id).cloned(),
+            is_calculate_allowed: !Self::is_halted()
+                && !oracle.is_paused()
+                && oracle.is_allow_calculate(value_id, now).unwrap_or(false),
+            is_degraded: oracle.is_degraded(value_id, now),
+            is_paused: oracle.is_paused(),
+            is_frozen: oracle.is_frozen(value_id),
+            frozen_value: oracle
+                .get_frozen_value(value_id)
+                .and_then(|frozen| frozen.value),
+        })
+    }
+
+    /// Getter for calculate value in oracle
+    ///
+    /// Value frozen by circuit breaker is not available, the previous one is returned
+    fn get_external_value(
+        oracle_id: T::OracleId,
+        value_id: usize,
+    ) -> Result<(T::ValueType, Moment<T>), Error<T>> {
+        Oracles::<T>::get(oracle_id)
+            .values
+            .get(value_id)
+            .ok_or(Error::<T>::WrongValueId)?
+            .get()
+            .ok_or(Error::<T>::NotCalculatedValue)
+    }
+
+    /// Value for consumer, that is served by fallback chain of value, when it is older
+    /// than its max age, degraded, frozen by circuit breaker or not calculated
+    ///
+    /// Consumer must be subscribed to permissioned oracle, fallbacks from permissioned oracles
+    /// without subscription are skipped. Return error of requested value, when no value of
+    /// fallback chain is available.
+    pub fn get_served_value(
+        consumer: &T::AccountId,
+        oracle_id: T::OracleId,
+        value_id: u8,
+    ) -> Result<ServedValue<T::OracleId, T::ValueType, Moment<T>>, dispatch::DispatchError> {
+        ensure!(
+            Self::is_subscribed(oracle_id, consumer),
+            Error::<T>::NotSubscribed
+        );
+        Self::serve_value(oracle_id, value_id, |oracle_id| {
+            Self::is_subscribed(*oracle_id, consumer)
+        })
+    }
+
+    /// Consumer can read oracle: oracle isn't permissioned or subscription is active
+    pub fn is_subscribed(oracle_id: T::OracleId, consumer: &T::AccountId) -> bool {
+        if Self::subscription_config(oracle_id).is_none() {
+            return true;
+        }
+
+        let oracle = Oracles::<T>::get(oracle_id);
+        let period = oracle
+            .period_handler
+            .get_period_number(Self::oracle_now(&oracle));
+        Self::subscription(oracle_id, consumer)
+            .map_or(false, |subscription| subscription.is_active(period))
+    }
+
+    /// Oracle can be referred by derived values and fallbacks of `who`: oracle isn't
+    /// permissioned or is owned by `who`
+    fn is_referable(oracle_id: T::OracleId, who: &T::AccountId) -> bool {
+        Self::subscription_config(oracle_id).is_none()
+            || Self::oracle_owner(oracle_id).as_ref() == Some(who)
+    }
+
+    fn serve_value(
+        oracle_id: T::OracleId,
+        value_id: u8,
+        is_allowed: impl Fn(&T::OracleId) -> bool,
+    ) -> Result<ServedValue<T::OracleId, T::ValueType, Moment<T>>, dispatch::DispatchError> {
+        let primary = ValueRef {
+            oracle_id,
+            value_id,
+        };
+        let error = match Self::get_fresh_value(primary) {
+            Ok(served) => return Ok(served),
+            Err(error) => error,
+        };
+
+        rstd::iter::successors(Self::fallback(oracle_id, value_id), |value| {
+            Self::fallback(value.oracle_id, value.value_id)
+        })
+        .take(MAX_FALLBACK_DEPTH)
+        .filter(|value| is_allowed(&value.oracle_id))
+        .find_map(|value| Self::get_fresh_value(value).ok())
+        .ok_or_else(|| error.into())
+    }
+
+    /// Calculated value, that is not older than its max age, degraded by heartbeat or frozen
+    /// by circuit breaker
+    fn get_fresh_value(
+        value_ref: ValueRef<T::OracleId>,
+    ) -> Result<ServedValue<T::OracleId, T::ValueType, Moment<T>>, Error<T>> {
+        ensure!(
+            Self::is_oracle_exists(value_ref.oracle_id),
+            Error::<T>::WrongOracleId
+        );
+        let (value, last_changed) =
+            Self::get_external_value(value_ref.oracle_id, value_ref.value_id as usize)?;
+
+        let oracle = Oracles::<T>::get(value_ref.oracle_id);
+        let now = Self::oracle_now(&oracle);
+        ensure!(
+            !oracle.is_frozen(value_ref.value_id as usize),
+            Error::<T>::ValueFrozen
+        );
+        ensure!(
+            !oracle.is_degraded(value_ref.value_id as usize, now),
+            Error::<T>::ValueDegraded
+        );
+        if let Some(max_age) = Self::max_age(value_ref.oracle_id, value_ref.value_id) {
+            ensure!(
+                now.saturating_sub(last_changed) <= max_age,
+                Error::<T>::ValueStale
+            );
+        }
+
+        Ok(ServedValue {
+            served_by: value_ref,
+            value,
+            decimals: oracle.decimals[value_ref.value_id as usize],
+            last_changed,
+        })
+    }
+
+    /// Calculate derived value of oracle by its formula for consumer
+    ///
+    /// Consumer must be subscribed to the oracle and permissioned oracles of formula inputs.
+    /// Return value with the oldest `last_changed` of formula inputs.
+    pub fn get_derived_value(
+        consumer: &T::AccountId,
+        oracle_id: T::OracleId,
+        derived_id: u8,
+    ) -> Result<(T::ValueType, Moment<T>), dispatch::DispatchError> {
+        ensure!(
+            Self::is_subscribed(oracle_id, consumer),
+            Error::<T>::NotSubscribed
+        );
+        let derived = Self::derived_values(oracle_id)
+            .remove(&derived_id)
+            .ok_or(Error::<T>::WrongValueId)?;
+
+        let mut oldest: Option<Moment<T>> = None;
+        let inputs = derived
+            .formula
+            .get_inputs()
+            .into_iter()
+            .map(|input| {
+                ensure!(
+                    Self::is_subscribed(input.oracle_id, consumer),
+                    Error::<T>::NotSubscribed
+                );
+                let (value, moment) =
+                    Self::get_external_value(input.oracle_id, input.value_id as usize)?;
+                oldest = Some(oldest.map_or(moment, |oldest| oldest.min(moment)));
+
+                let decimals = Oracles::<T>::get(input.oracle_id).decimals[input.value_id as usize];
+                Ok((to_i128(value), decimals))
+            })
+            .collect::<Result<Vec<_>, Error<T>>>()?;
+
+        let value = derived
+            .formula
+            .calculate(&inputs, derived.decimals)
+            .ok_or(Error::<T>::FormulaError)?;
+        Ok((from_i128(value), oldest.ok_or(Error::<T>::WrongFormula)?))
+    }
+
+    fn get_or_calculate_external_value(
+        origin: T::Origin,
+        oracle_id: T::OracleId,
+        value_id: usize,
+    ) -> Result<(T::ValueType, Moment<T>), dispatch::DispatchError> {
+        match Self::get_external_value(oracle_id, value_id) {
+            Err(Error::<T>::NotCalculatedValue) => {
                 Self::calculate(origin, oracle_id, value_id as u8)?;
                 Ok(Self::get_external_value(oracle_id, value_id)?)
             }
+            result => Ok(result?),
         }
     }
 }
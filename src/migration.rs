@@ -0,0 +1,205 @@
+use codec::Decode;
+use frame_support::storage::{unhashed, StorageMap, StorageValue};
+use rstd::collections::btree_map::BTreeMap;
+use rstd::prelude::Vec;
+use sp_arithmetic::traits::SimpleArithmetic;
+
+use crate::clock::ClockSource;
+use crate::external_value::{ExternalValue, ValueArithmetic};
+use crate::oracle::Oracle;
+use crate::period_handler::PeriodHandler;
+use crate::{AccountId, Module, Moment, Oracles, StorageVersion, Trait};
+
+type TableId<T> = <T as tablescore::Trait>::TableId;
+
+/// Version of storage layout, that is written by current code
+///
+/// - 0 - layout before storage versioning, there is no version in storage
+/// - 1 - oracle with quorum, clock and settings of values, `History` of past periods
+pub const STORAGE_VERSION: u32 = 1;
+
+/// Migrate storage from layout of stored version to the current one
+///
+/// Called on runtime upgrade, does nothing for storage of current version. Version isn't
+/// changed, when some oracles failed to migrate, so the migration is repeated on the next
+/// runtime upgrade.
+pub fn migrate<T: Trait>() {
+    let failed = if Module::<T>::storage_version() < 1 {
+        migrate_v0_to_v1::<T>()
+    } else {
+        0
+    };
+
+    if failed == 0 {
+        StorageVersion::put(STORAGE_VERSION);
+    } else {
+        sp_runtime::print("Oracle storage migration failed, count of failed oracles:");
+        sp_runtime::print(failed);
+    }
+}
+
+/// Oracles, that can't be decoded in version 0 layout, are left unchanged
+///
+/// Oracles of current layout are skipped, so the migration can be repeated after failures.
+/// Layouts are told apart by decoding of all stored bytes, as prefix of version 0 oracle
+/// can be decodable in current layout. Owners of oracles aren't stored in version 0, they
+/// are set by root with `set_oracle_owner`. Return count of oracles failed to migrate.
+fn migrate_v0_to_v1<T: Trait>() -> u32 {
+    let mut failed = 0u32;
+
+    Module::<T>::oracle_ids().into_iter().for_each(|oracle_id| {
+        let raw = unhashed::get_raw(&Oracles::<T>::hashed_key_for(oracle_id)).unwrap_or_default();
+        let current =
+            decode_exact::<Oracle<TableId<T>, T::ValueType, Moment<T>, AccountId<T>>>(&raw);
+        if current.is_some() {
+            return;
+        }
+
+        let oracle =
+            decode_exact::<v0::Oracle<TableId<T>, T::ValueType, Moment<T>, AccountId<T>>>(&raw)
+                .and_then(v0::Oracle::migrate);
+
+        match oracle {
+            Some(oracle) => {
+                if let Some((period, data)) = oracle.get_prev_period_data().cloned() {
+                    Module::<T>::store_history(oracle_id, period, data, oracle.get_history_depth());
+                }
+                Oracles::<T>::insert(oracle_id, oracle);
+            }
+            None => failed = failed.saturating_add(1),
+        }
+    });
+
+    failed
+}
+
+/// Decode value, which takes all bytes of `raw`
+fn decode_exact<D: Decode>(raw: &[u8]) -> Option<D> {
+    let mut input = raw;
+    D::decode(&mut input).ok().filter(|_| input.is_empty())
+}
+
+/// Layout of storage version 0
+pub mod v0 {
+    use super::*;
+
+    #[derive(Decode)]
+    pub struct Oracle<TableId, ValueType, Moment, SourceId: Ord> {
+        pub name: Vec<u8>,
+        pub table: TableId,
+        pub source_limit: u8,
+        pub period_handler: PeriodHandler<Moment>,
+        pub sources: BTreeMap<SourceId, Vec<ExternalValue<ValueType, Moment>>>,
+        pub names: Vec<Vec<u8>>,
+        pub values: Vec<ExternalValue<ValueType, Moment>>,
+        pub last_push_period: Option<Moment>,
+
+        /// Data pushed in the previous period of pushes
+        pub prev_period_source: BTreeMap<SourceId, Vec<Option<ExternalValue<ValueType, Moment>>>>,
+    }
+
+    #[derive(Decode)]
+    pub struct PeriodHandler<Moment> {
+        pub begin: Moment,
+        pub period: Moment,
+        pub aggregate_part: Moment,
+        pub last_sources_update: Option<Moment>,
+    }
+
+    #[derive(Decode)]
+    pub struct ExternalValue<ValueType, Moment> {
+        pub value: Option<ValueType>,
+        pub last_changed: Option<Moment>,
+    }
+
+    impl<ValueType, Moment: Clone> ExternalValue<ValueType, Moment> {
+        /// Pushed values of version 0 were observed at the moment of push
+        pub fn migrate(self) -> super::ExternalValue<ValueType, Moment> {
+            super::ExternalValue {
+                value: self.value,
+                observed: self.last_changed.clone(),
+                last_changed: self.last_changed,
+            }
+        }
+    }
+
+    impl<Moment: Default + Copy + SimpleArithmetic> PeriodHandler<Moment> {
+        /// Periods of version 0 were measured by timestamp
+        pub fn migrate(self) -> Option<super::PeriodHandler<Moment>> {
+            let mut handler = super::PeriodHandler::with_clock(
+                ClockSource::Timestamp,
+                self.begin,
+                self.period,
+                self.aggregate_part,
+            )
+            .ok()?;
+            if let Some(moment) = self.last_sources_update {
+                handler.set_sources_updated(moment);
+            }
+            Some(handler)
+        }
+    }
+
+    impl<
+            TableId: Default,
+            ValueType: Default + ValueArithmetic,
+            Moment: Default + Copy + SimpleArithmetic,
+            SourceId: Default + Ord + Clone,
+        > Oracle<TableId, ValueType, Moment, SourceId>
+    {
+        /// Oracle in current layout with default settings, `None` for inconsistent oracle
+        ///
+        /// Data of the previous period becomes data of the period, in which it was pushed
+        pub fn migrate(self) -> Option<super::Oracle<TableId, ValueType, Moment, SourceId>> {
+            let period_handler = self.period_handler.migrate()?;
+
+            let prev_period = self
+                .prev_period_source
+                .values()
+                .flatten()
+                .flatten()
+                .find_map(|value| value.last_changed)
+                .map(|moment| period_handler.get_period_number(moment));
+            let prev_period_data = prev_period.map(|period| {
+                let data = self
+                    .prev_period_source
+                    .into_iter()
+                    .map(|(source, values)| {
+                        let values = values
+                            .into_iter()
+                            .map(|value| value.map(ExternalValue::migrate))
+                            .collect();
+                        (source, values)
+                    })
+                    .collect();
+                (period, data)
+            });
+
+            let sources = self
+                .sources
+                .into_iter()
+                .map(|(source, values)| {
+                    let values = values.into_iter().map(ExternalValue::migrate).collect();
+                    (source, values)
+                })
+                .collect();
+            let values = self
+                .values
+                .into_iter()
+                .map(ExternalValue::migrate)
+                .collect();
+
+            let mut oracle = super::Oracle::new(
+                self.name,
+                self.table,
+                period_handler,
+                self.source_limit,
+                self.names,
+            );
+            oracle
+                .restore_data(sources, values, self.last_push_period, prev_period_data)
+                .ok()?;
+            Some(oracle)
+        }
+    }
+}
@@ -6,6 +6,7 @@ use sp_runtime::{
     traits::{BlakeTwo256, IdentityLookup},
     Perbill,
 };
+use std::cell::RefCell;
 
 impl_outer_origin! {
     pub enum Origin for Test {}
@@ -67,11 +68,39 @@ impl crate::Trait for Test {
     type Event = ();
     type OracleId = u32;
     type ValueType = u128;
+    type OnSourcePenalty = ();
+    type OnOracleUpdate = (UpdateRecorder, ());
+}
+
+thread_local! {
+    static UPDATES: RefCell<Vec<(u32, u8, u128, u128)>> = RefCell::new(Vec::new());
+}
+
+/// Records values calculated in oracles
+pub struct UpdateRecorder;
+
+impl UpdateRecorder {
+    /// Take recorded updates
+    pub fn take() -> Vec<(u32, u8, u128, u128)> {
+        UPDATES.with(|updates| updates.replace(Vec::new()))
+    }
+}
+
+impl crate::OnOracleUpdate<u32, u128, u128> for UpdateRecorder {
+    fn on_oracle_update(oracle_id: u32, value_id: u8, value: u128, moment: u128) {
+        UPDATES.with(|updates| {
+            updates
+                .borrow_mut()
+                .push((oracle_id, value_id, value, moment))
+        });
+    }
 }
 
 pub type OracleModule = Module<Test>;
+pub type SystemModule = system::Module<Test>;
 pub type TablescoreModule = tablescore::Module<Test>;
 pub type TimestampModule = timestamp::Module<Test>;
+pub type AssetsModule = assets::Module<Test>;
 
 pub type AccountId = <Test as system::Trait>::AccountId;
 pub type TableId = <Test as tablescore::Trait>::TableId;
@@ -154,7 +183,8 @@ pub fn get_median_value(moment: usize, asset_id: usize, offsets: Vec<Balance>) -
 
     let middle = offsets.len() / 2;
     match offsets.len() {
-        0 | 1 => 0,
+        0 => 0,
+        1 => offsets[0],
         len if len % 2 == 0 => (offsets[middle - 1] + offsets[middle]) / 2,
         _len => offsets[middle],
     }
@@ -166,7 +196,13 @@ pub fn get_median_values(moment: usize, offsets: Vec<Balance>) -> Vec<Balance> {
         .collect()
 }
 
+pub type GenesisOracle = crate::OracleGenesis<AccountId, u32, u128, u128>;
+
 pub fn new_test_ext() -> sp_io::TestExternalities {
+    new_test_ext_with_oracles(vec![])
+}
+
+pub fn new_test_ext_with_oracles(oracles: Vec<GenesisOracle>) -> sp_io::TestExternalities {
     let mut t = system::GenesisConfig::default()
         .build_storage::<Test>()
         .unwrap();
@@ -184,5 +220,9 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
     .assimilate_storage(&mut t)
     .unwrap();
 
+    crate::GenesisConfig::<Test> { oracles }
+        .assimilate_storage(&mut t)
+        .unwrap();
+
     t.into()
 }
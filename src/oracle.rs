@@ -2,13 +2,46 @@ use codec::{Decode, Encode};
 use rstd::cmp::Ord;
 use rstd::collections::btree_map::BTreeMap;
 use rstd::prelude::Vec;
-use sp_arithmetic::traits::SimpleArithmetic;
-
-use crate::external_value::{get_median, ExternalValue, Median};
+use sp_arithmetic::traits::{SimpleArithmetic, Zero};
+use sp_arithmetic::Perbill;
+
+use crate::breaker::CircuitBreaker;
+use crate::dispersion::Dispersion;
+use crate::external_value::{
+    average, get_deviation, get_median, get_weighted_majority, ExternalValue, Median,
+    ValueArithmetic,
+};
+use crate::heartbeat::Heartbeat;
 use crate::period_handler::{Part, PeriodHandler};
 
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
 type RawString = Vec<u8>;
 
+/// Data pushed by sources in one period, `None` for values calculated in the period
+pub type PeriodData<SourceId, ValueType, Moment> =
+    BTreeMap<SourceId, Vec<Option<ExternalValue<ValueType, Moment>>>>;
+
+/// Stake of each source, that weights its votes in majority aggregation
+pub type Stakes<SourceId> = BTreeMap<SourceId, u128>;
+
+/// Max count of past periods with stored pushed data
+pub const MAX_HISTORY_DEPTH: u32 = 100;
+
+/// Data pushed in past period and moved out of oracle to be stored in history
+#[derive(Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ArchivedPeriod<SourceId, ValueType, Moment> {
+    pub period: Moment,
+
+    /// Pushed data of values, that were not calculated in the period
+    pub data: PeriodData<SourceId, ValueType, Moment>,
+
+    /// Sources of the period and whether each of them pushed in it
+    pub pushes: Vec<(SourceId, bool)>,
+}
+
 #[derive(Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub enum OracleError {
@@ -33,10 +66,85 @@ pub enum OracleError {
     /// Source not in list
     SourcePermissionDenied,
 
+    /// Value is not frozen by circuit breaker
+    NotFrozenValue(usize),
+
+    /// Period is not in the past
+    NotPastPeriod,
+
+    /// No stored pushed data for period
+    PeriodDataNotFound,
+
+    /// Count of stored periods is zero or more than `MAX_HISTORY_DEPTH`
+    WrongHistoryDepth(u32),
+
+    /// Quorum is zero
+    WrongQuorum,
+
+    /// No category has enough stake of pushed votes
+    NoMajority,
+
+    /// Value is byte payload, that is finalised by hash consensus instead of calculation
+    PayloadValue(usize),
+
+    /// Observation moment of pushed values is out of tolerance of current moment
+    WrongObservationTime,
+
     /// Unknown error in calculate process
     CalculationError,
 }
 
+/// Reason why value calculation is allowed
+#[derive(Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum CalculationTrigger {
+    /// Calculation in calculate part or lazy calculation of previous period
+    Period,
+
+    /// Early calculation in aggregate part, when values pushed in current period
+    /// deviate from the last calculated value
+    Deviation,
+}
+
+/// How values pushed by sources are aggregated into calculated value
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub enum Aggregation {
+    /// Median of numeric values
+    Median,
+
+    /// Category (e.g. 0 - false, 1 - true) pushed by sources with the largest bonded stake,
+    /// which is at least threshold part of stake of all pushed votes
+    Majority(Perbill),
+
+    /// Byte payload pushed separately from numeric values and finalised, when
+    /// threshold part of sources push payloads with the same hash
+    Hash(Perbill),
+}
+
+impl Default for Aggregation {
+    fn default() -> Self {
+        Aggregation::Median
+    }
+}
+
+/// How sources of oracle are selected
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub enum SourceMode {
+    /// Sources are winners of voting in tablescore, updated every period
+    Tablescore,
+
+    /// Sources are set by oracle owner and not updated from tablescore
+    Fixed,
+}
+
+impl Default for SourceMode {
+    fn default() -> Self {
+        SourceMode::Tablescore
+    }
+}
+
 #[derive(Encode, Decode, Clone, Eq, PartialEq, Default)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct Oracle<
@@ -54,23 +162,61 @@ pub struct Oracle<
     /// Lower limit of the number of sources
     source_limit: u8,
 
+    /// Part of sources, that must push value for calculation
+    ///
+    /// `None` - `source_limit` values are required
+    quorum: Option<Perbill>,
+
     /// Work with aggregate and calculate periods in oracle
     pub period_handler: PeriodHandler<Moment>,
 
     /// All pushed by sources data
     sources: BTreeMap<SourceId, Vec<ExternalValue<ValueType, Moment>>>,
 
+    /// How sources are selected
+    source_mode: SourceMode,
+
     /// Names of external values
     pub names: Vec<RawString>,
 
+    /// Count of decimal digits in external values, the value is `value / 10^decimals`
+    pub decimals: Vec<u8>,
+
+    /// Aggregation of each value
+    aggregations: Vec<Aggregation>,
+
     /// Vector of calculated values
     pub values: Vec<ExternalValue<ValueType, Moment>>,
 
     /// The last period when one of the sources pushed the values
     last_push_period: Option<Moment>,
 
-    /// The `sources` field from previous period for lazy calculating in current period aggregate part
-    prev_period_source: BTreeMap<SourceId, Vec<Option<ExternalValue<ValueType, Moment>>>>,
+    /// The `sources` field from the last past period of pushes for lazy calculating
+    ///
+    /// Data of all past periods is stored in history outside of oracle
+    prev_period_data: Option<(Moment, PeriodData<SourceId, ValueType, Moment>)>,
+
+    /// Count of past periods with stored pushed data
+    history_depth: u32,
+
+    /// Deviation of values pushed in current period from the last calculated value,
+    /// after which the value can be calculated without waiting for calculate part
+    deviation_threshold: Option<Perbill>,
+
+    /// Heartbeats of values, that must be calculated at least every N periods
+    heartbeats: Vec<Option<Heartbeat<Moment>>>,
+
+    /// Circuit breakers of values, that freeze value on too large change
+    breakers: Vec<Option<CircuitBreaker<ValueType, Moment>>>,
+
+    /// Spread of values pushed by sources in the last calculation of each value
+    dispersions: Vec<Option<Dispersion<ValueType>>>,
+
+    /// Oracle doesn't accept pushes and calculations
+    is_paused: bool,
+
+    /// Max difference between observation moment of pushed values and moment of push
+    observation_tolerance: Moment,
 }
 
 impl<
@@ -83,11 +229,84 @@ impl<
     pub fn get_table(&self) -> &TableId {
         &self.table
     }
+
+    pub fn get_source_limit(&self) -> u8 {
+        self.source_limit
+    }
+
+    pub fn get_sources(&self) -> impl Iterator<Item = &SourceId> {
+        self.sources.keys()
+    }
+
+    pub fn get_quorum(&self) -> Option<Perbill> {
+        self.quorum
+    }
+
+    /// Require `quorum` part of current sources to push value for calculation
+    ///
+    /// `None` - require `source_limit` values
+    pub fn set_quorum(&mut self, quorum: Option<Perbill>) -> Result<(), OracleError> {
+        if matches!(quorum, Some(quorum) if quorum.is_zero()) {
+            return Err(OracleError::WrongQuorum);
+        }
+
+        self.quorum = quorum;
+        Ok(())
+    }
+
+    /// Count of pushed values required for calculation, at least one
+    pub fn get_required_values(&self) -> usize {
+        match self.quorum {
+            Some(quorum) => self.get_required_sources(quorum),
+            None => self.source_limit.max(1) as usize,
+        }
+    }
+
+    /// The least count of current sources, that is at least `part` of them, at least one
+    pub fn get_required_sources(&self, part: Perbill) -> usize {
+        let sources = self.sources.len() as u32;
+        (1..=sources)
+            .find(|count| Perbill::from_rational_approximation(*count, sources) >= part)
+            .unwrap_or(1) as usize
+    }
+
+    pub fn get_deviation_threshold(&self) -> Option<Perbill> {
+        self.deviation_threshold
+    }
+
+    pub fn set_deviation_threshold(&mut self, threshold: Option<Perbill>) {
+        self.deviation_threshold = threshold;
+    }
+
+    pub fn get_source_mode(&self) -> SourceMode {
+        self.source_mode
+    }
+
+    /// Select sources by voting in tablescore from the next sources update
+    pub fn set_tablescore_sources(&mut self) {
+        self.source_mode = SourceMode::Tablescore;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.is_paused
+    }
+
+    pub fn get_observation_tolerance(&self) -> Moment {
+        self.observation_tolerance.clone()
+    }
+
+    pub fn set_observation_tolerance(&mut self, tolerance: Moment) {
+        self.observation_tolerance = tolerance;
+    }
+
+    pub fn get_aggregation(&self, value_id: usize) -> Aggregation {
+        self.aggregations.get(value_id).copied().unwrap_or_default()
+    }
 }
 
 impl<
         TableId: Default,
-        ValueType: Default + Copy + SimpleArithmetic,
+        ValueType: Default + ValueArithmetic,
         Moment: Default + Copy + SimpleArithmetic,
         SourceId: Default + Ord + Clone,
     > Oracle<TableId, ValueType, Moment, SourceId>
@@ -104,16 +323,58 @@ impl<
             table,
             period_handler,
             source_limit,
+            quorum: None,
             sources: BTreeMap::default(),
+            source_mode: SourceMode::Tablescore,
             values: rstd::iter::repeat_with(ExternalValue::<ValueType, Moment>::default)
                 .take(assets_name.len())
                 .collect(),
+            heartbeats: rstd::iter::repeat_with(|| None)
+                .take(assets_name.len())
+                .collect(),
+            breakers: rstd::iter::repeat_with(|| None)
+                .take(assets_name.len())
+                .collect(),
+            dispersions: rstd::iter::repeat_with(|| None)
+                .take(assets_name.len())
+                .collect(),
+            decimals: rstd::iter::repeat(0).take(assets_name.len()).collect(),
+            aggregations: rstd::iter::repeat(Aggregation::Median)
+                .take(assets_name.len())
+                .collect(),
             names: assets_name,
             last_push_period: None,
-            prev_period_source: BTreeMap::default(),
+            prev_period_data: None,
+            history_depth: 1,
+            deviation_threshold: None,
+            is_paused: false,
+            observation_tolerance: Moment::zero(),
         }
     }
 
+    /// Stop accepting pushes and calculations
+    pub fn pause(&mut self) {
+        self.is_paused = true;
+    }
+
+    /// Resume oracle work from new period, that starts at `now`
+    ///
+    /// Data pushed before pause is dropped and heartbeats are counted from the new period
+    pub fn resume(&mut self, now: Moment) {
+        self.is_paused = false;
+        self.period_handler.restart(now);
+
+        self.clear_pushed_data();
+        self.prev_period_data = None;
+        self.last_push_period = None;
+
+        let current_period = self.period_handler.get_period_number(now);
+        self.heartbeats
+            .iter_mut()
+            .filter_map(Option::as_mut)
+            .for_each(|heartbeat| heartbeat.beat(current_period));
+    }
+
     /// Count of values inside oracle
     pub fn get_values_count(&self) -> usize {
         self.names.len()
@@ -123,6 +384,10 @@ impl<
         self.sources.is_empty()
     }
 
+    pub fn is_source(&self, source: &SourceId) -> bool {
+        self.sources.contains_key(source)
+    }
+
     pub fn is_value_id_correct(&self, value_id: usize) -> Result<(), OracleError> {
         if value_id < self.get_values_count() {
             Ok(())
@@ -142,15 +407,220 @@ impl<
     ///
     /// Can return `OracleError::WrongValueId(value_id)`
     pub fn is_allow_calculate(&self, value_id: usize, now: Moment) -> Result<bool, OracleError> {
+        Ok(self.get_calculation_trigger(value_id, now)?.is_some())
+    }
+
+    /// Why calculation of a specific by id value is allowed now
+    ///
+    /// `None` if calculation is not allowed, payload values are never calculated
+    pub fn get_calculation_trigger(
+        &self,
+        value_id: usize,
+        now: Moment,
+    ) -> Result<Option<CalculationTrigger>, OracleError> {
         self.is_value_id_correct(value_id)?;
-        Ok(self
-            .period_handler
-            .is_allow_calculate(self.values[value_id].last_changed, now))
+
+        Ok(
+            if matches!(self.get_aggregation(value_id), Aggregation::Hash(_)) {
+                None
+            } else if self
+                .period_handler
+                .is_allow_calculate(self.get_last_calculated(value_id).last_changed, now)
+            {
+                Some(CalculationTrigger::Period)
+            } else if self.is_deviation_exceeded(value_id, now) {
+                Some(CalculationTrigger::Deviation)
+            } else {
+                None
+            },
+        )
+    }
+
+    /// Is median of values pushed in current aggregate part deviates from the last
+    /// calculated value more than threshold
+    fn is_deviation_exceeded(&self, value_id: usize, now: Moment) -> bool {
+        let (threshold, last_value) = match (
+            self.deviation_threshold,
+            self.get_last_calculated(value_id).value,
+        ) {
+            (Some(threshold), Some(last_value)) => (threshold, last_value),
+            _ => return false,
+        };
+
+        if self.get_aggregation(value_id) != Aggregation::Median
+            || !self.period_handler.is_allow_aggregate(now)
+            || self.last_push_period != Some(self.period_handler.get_period_number(now))
+        {
+            return false;
+        }
+
+        let values: Vec<ValueType> = self
+            .sources
+            .iter()
+            .filter_map(|(_source, assets)| assets.get(value_id).and_then(|asset| asset.value))
+            .collect();
+
+        if self.get_required_values() > values.len() {
+            return false;
+        }
+
+        match Self::calculate_median(values) {
+            Ok(median) => get_deviation(median, last_value) > threshold,
+            Err(_) => false,
+        }
     }
 
-    pub fn add_assets(&mut self, name: RawString) {
+    pub fn add_assets(&mut self, name: RawString, decimals: u8) {
         self.names.push(name);
+        self.decimals.push(decimals);
+        self.aggregations.push(Aggregation::Median);
         self.values.push(ExternalValue::default());
+        self.heartbeats.push(None);
+        self.breakers.push(None);
+        self.dispersions.push(None);
+    }
+
+    /// Set count of decimal digits for each value
+    pub fn set_decimals(&mut self, decimals: Vec<u8>) -> Result<(), OracleError> {
+        if decimals.len() != self.get_values_count() {
+            return Err(OracleError::WrongValuesCount(
+                self.get_values_count(),
+                decimals.len(),
+            ));
+        }
+
+        self.decimals = decimals;
+        Ok(())
+    }
+
+    /// Set aggregation of value, e.g. majority for categorical values
+    pub fn set_aggregation(
+        &mut self,
+        value_id: usize,
+        aggregation: Aggregation,
+    ) -> Result<(), OracleError> {
+        self.is_value_id_correct(value_id)?;
+
+        self.aggregations[value_id] = aggregation;
+        Ok(())
+    }
+
+    /// Deviation of pushed value from calculated one in terms of value aggregation
+    ///
+    /// Category either matches calculated one or deviates by 100%
+    pub fn get_value_deviation(
+        &self,
+        value_id: usize,
+        pushed: ValueType,
+        calculated: ValueType,
+    ) -> Perbill {
+        match self.get_aggregation(value_id) {
+            Aggregation::Median => get_deviation(pushed, calculated),
+            Aggregation::Majority(_) | Aggregation::Hash(_) if pushed == calculated => {
+                Perbill::zero()
+            }
+            Aggregation::Majority(_) | Aggregation::Hash(_) => Perbill::one(),
+        }
+    }
+
+    /// Freeze value, when calculated value deviates from the previous one more than `threshold`
+    ///
+    /// Change of `threshold` keeps the frozen value, `None` - remove circuit breaker and drop
+    /// the frozen value
+    pub fn set_circuit_breaker(
+        &mut self,
+        value_id: usize,
+        threshold: Option<Perbill>,
+    ) -> Result<(), OracleError> {
+        self.is_value_id_correct(value_id)?;
+
+        match (&mut self.breakers[value_id], threshold) {
+            (Some(breaker), Some(threshold)) => breaker.threshold = threshold,
+            (breaker, threshold) => *breaker = threshold.map(CircuitBreaker::new),
+        }
+        Ok(())
+    }
+
+    /// Spread of values pushed by sources in the last calculation of value
+    pub fn get_dispersion(&self, value_id: usize) -> Option<&Dispersion<ValueType>> {
+        self.dispersions.get(value_id)?.as_ref()
+    }
+
+    /// Value is frozen by circuit breaker until confirmation
+    pub fn is_frozen(&self, value_id: usize) -> bool {
+        self.get_frozen_value(value_id).is_some()
+    }
+
+    /// Calculated value held by tripped circuit breaker, `values` keep the previous one
+    pub fn get_frozen_value(&self, value_id: usize) -> Option<&ExternalValue<ValueType, Moment>> {
+        self.breakers.get(value_id)?.as_ref()?.tripped.as_ref()
+    }
+
+    /// The last calculated value, including one frozen by circuit breaker
+    fn get_last_calculated(&self, value_id: usize) -> &ExternalValue<ValueType, Moment> {
+        self.get_frozen_value(value_id)
+            .unwrap_or(&self.values[value_id])
+    }
+
+    /// Unfreeze value after check by owner, frozen value replaces the previous one
+    pub fn confirm_value(&mut self, value_id: usize) -> Result<(), OracleError> {
+        self.is_value_id_correct(value_id)?;
+
+        let frozen = self.breakers[value_id]
+            .as_mut()
+            .and_then(CircuitBreaker::reset)
+            .ok_or(OracleError::NotFrozenValue(value_id))?;
+
+        self.values[value_id] = frozen;
+        Ok(())
+    }
+
+    /// Require calculation of value at least every `periods` periods
+    ///
+    /// `None` - remove requirement
+    pub fn set_heartbeat(
+        &mut self,
+        value_id: usize,
+        periods: Option<u32>,
+        now: Moment,
+    ) -> Result<(), OracleError> {
+        self.is_value_id_correct(value_id)?;
+
+        let current_period = self.period_handler.get_period_number(now);
+        self.heartbeats[value_id] = periods.map(|periods| Heartbeat::new(periods, current_period));
+        Ok(())
+    }
+
+    /// Mark values, which were not calculated in time, as degraded
+    ///
+    /// Return ids of values degraded by this check. Heartbeats are not checked in pause.
+    pub fn check_heartbeats(&mut self, now: Moment) -> Vec<usize> {
+        if self.is_paused {
+            return Vec::new();
+        }
+
+        let current_period = self.period_handler.get_period_number(now);
+
+        self.heartbeats
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(value_id, heartbeat)| match heartbeat {
+                Some(heartbeat) if heartbeat.check(current_period) => Some(value_id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Value was not calculated in time according to its heartbeat
+    pub fn is_degraded(&self, value_id: usize, now: Moment) -> bool {
+        let current_period = self.period_handler.get_period_number(now);
+
+        match self.heartbeats.get(value_id) {
+            Some(Some(heartbeat)) => {
+                heartbeat.is_missed || (!self.is_paused && heartbeat.is_expired(current_period))
+            }
+            _ => false,
+        }
     }
 
     /// Update sources for oracle
@@ -185,12 +655,122 @@ impl<
         }
     }
 
-    /// Store pushed data for previous period for late lazy-calculate
-    fn store_pushed_data(&mut self, period_for_store: Moment) {
-        // Store only for not calculated in period_for_store values
-        let is_need_store_flags: Vec<bool> = self
-            .values
+    /// Set fixed sources, that aren't updated from tablescore
+    pub fn set_fixed_sources(&mut self, sources: Vec<SourceId>) -> Result<(), OracleError> {
+        let count = sources
+            .iter()
+            .collect::<rstd::collections::btree_set::BTreeSet<_>>()
+            .len();
+        if count < self.source_limit as usize {
+            return Err(OracleError::FewSources(self.source_limit as usize, count));
+        }
+
+        self.update_sources(sources.into_iter())?;
+        self.source_mode = SourceMode::Fixed;
+        Ok(())
+    }
+
+    /// Set calculated values, e.g. the last known values of oracle in genesis
+    pub fn set_initial_values(
+        &mut self,
+        values: Vec<ValueType>,
+        now: Moment,
+    ) -> Result<(), OracleError> {
+        if values.len() != self.get_values_count() {
+            return Err(OracleError::WrongValuesCount(
+                self.get_values_count(),
+                values.len(),
+            ));
+        }
+
+        self.values = values
+            .into_iter()
+            .map(|value| ExternalValue::new(value, now))
+            .collect();
+        Ok(())
+    }
+
+    /// Restore pushed data and calculated values, e.g. of oracle in previous storage layout
+    pub fn restore_data(
+        &mut self,
+        sources: BTreeMap<SourceId, Vec<ExternalValue<ValueType, Moment>>>,
+        values: Vec<ExternalValue<ValueType, Moment>>,
+        last_push_period: Option<Moment>,
+        prev_period_data: Option<(Moment, PeriodData<SourceId, ValueType, Moment>)>,
+    ) -> Result<(), OracleError> {
+        if values.len() != self.get_values_count() {
+            return Err(OracleError::WrongValuesCount(
+                self.get_values_count(),
+                values.len(),
+            ));
+        }
+
+        self.sources = sources;
+        self.values = values;
+        self.last_push_period = last_push_period;
+        self.prev_period_data = prev_period_data;
+        Ok(())
+    }
+
+    /// Data of the last past period of pushes
+    pub fn get_prev_period_data(
+        &self,
+    ) -> Option<&(Moment, PeriodData<SourceId, ValueType, Moment>)> {
+        self.prev_period_data.as_ref()
+    }
+
+    pub fn get_history_depth(&self) -> u32 {
+        self.history_depth
+    }
+
+    /// Keep pushed data for `depth` past periods
+    pub fn set_history_depth(&mut self, depth: u32) -> Result<(), OracleError> {
+        if depth == 0 || depth > MAX_HISTORY_DEPTH {
+            return Err(OracleError::WrongHistoryDepth(depth));
+        }
+
+        self.history_depth = depth;
+        Ok(())
+    }
+
+    /// Move data pushed in past period out of oracle
+    ///
+    /// Oracle keeps only data of the last past period for lazy calculation, returned data
+    /// must be stored in history for late calculations. `None` if there is no data of
+    /// past period.
+    pub fn archive_pushed_data(
+        &mut self,
+        current_period: Moment,
+    ) -> Option<ArchivedPeriod<SourceId, ValueType, Moment>> {
+        let period = self
+            .last_push_period
+            .filter(|period| *period != current_period)?;
+
+        let pushes = self
+            .sources
             .iter()
+            .map(|(source, external_values)| {
+                let is_pushed = external_values.iter().any(|ext| ext.value.is_some());
+                (source.clone(), is_pushed)
+            })
+            .collect();
+
+        let data = self.get_pushed_data(period);
+        self.prev_period_data = Some((period, data.clone()));
+        self.clear_pushed_data();
+        self.last_push_period = None;
+        Some(ArchivedPeriod {
+            period,
+            data,
+            pushes,
+        })
+    }
+
+    /// Pushed data for previous period for late lazy-calculate
+    fn get_pushed_data(&self, period_for_store: Moment) -> PeriodData<SourceId, ValueType, Moment> {
+        // Store only for not calculated in period_for_store values
+        let is_need_store_flags: Vec<bool> = (0..self.get_values_count())
+            .map(|value_id| self.get_last_calculated(value_id))
             .map(|external| {
                 if let Some(moment) = external.last_changed {
                     self.period_handler.get_period_number(moment) != period_for_store
@@ -200,8 +780,7 @@ impl<
             })
             .collect();
 
-        self.prev_period_source = self
-            .sources
+        self.sources
             .iter()
             .map(|(source, external_vec)| {
                 let data: Vec<Option<ExternalValue<ValueType, Moment>>> = external_vec
@@ -217,7 +796,7 @@ impl<
                     .collect();
                 (source.clone(), data)
             })
-            .collect();
+            .collect()
     }
 
     fn clear_pushed_data(&mut self) {
@@ -237,13 +816,35 @@ impl<
     where
         I: Iterator<Item = ValueType>,
     {
+        self.push_observed_values(source, now, now, new_values)
+    }
+
+    /// Push values observed by source at `observed` moment
+    ///
+    /// `observed` must be within observation tolerance of `now`
+    pub fn push_observed_values<I>(
+        &mut self,
+        source: &SourceId,
+        now: Moment,
+        observed: Moment,
+        new_values: I,
+    ) -> Result<(), OracleError>
+    where
+        I: Iterator<Item = ValueType>,
+    {
+        let difference = if observed > now {
+            observed - now
+        } else {
+            now - observed
+        };
+        if difference > self.observation_tolerance {
+            return Err(OracleError::WrongObservationTime);
+        }
+
         let current = self.period_handler.get_period_number(now);
 
         // If this is first push in period - we store and clean previous sources data
-        if matches!(self.last_push_period, Some(previous) if previous != current) {
-            self.store_pushed_data(self.last_push_period.unwrap());
-            self.clear_pushed_data();
-        }
+        self.archive_pushed_data(current);
         self.last_push_period = Some(current);
 
         self.sources
@@ -252,44 +853,100 @@ impl<
                 external_values
                     .iter_mut()
                     .zip(new_values)
-                    .for_each(|(external_value, new)| external_value.update(new, now));
+                    .for_each(|(external_value, new)| {
+                        external_value.update_observed(new, now, observed)
+                    });
             })
             .ok_or(OracleError::SourcePermissionDenied)
     }
 
-    fn get_actual_value_variants(
+    /// Values pushed by each source of the set used for calculation by `trigger` at `now`
+    ///
+    /// `None` means that the source has not pushed this value
+    pub fn get_source_values(
         &self,
-        ex_asset_id: usize,
+        value_id: usize,
+        trigger: CalculationTrigger,
         now: Moment,
-    ) -> Result<Vec<&ValueType>, OracleError> {
-        self.is_value_id_correct(ex_asset_id)?;
-
-        Ok(match self.period_handler.get_part(now) {
+    ) -> Result<Vec<(SourceId, Option<ValueType>)>, OracleError> {
+        Ok(self
+            .get_source_observations(value_id, trigger, now)?
+            .into_iter()
+            .map(|(source, observation)| (source, observation.map(|(value, _observed)| value)))
+            .collect())
+    }
+
+    /// Period of data used for calculation by `trigger` at `now`
+    ///
+    /// Lazy calculation uses data of the past period, other ones use data of current period
+    pub fn get_data_period(&self, trigger: CalculationTrigger, now: Moment) -> Moment {
+        let current = self.period_handler.get_period_number(now);
+        match (trigger, self.period_handler.get_part(now)) {
+            (CalculationTrigger::Period, Part::Aggregate) => self
+                .prev_period_data
+                .as_ref()
+                .map_or(current, |(period, _data)| *period),
+            _ => current,
+        }
+    }
+
+    /// Values pushed by each source with their observation moments
+    fn get_source_observations(
+        &self,
+        value_id: usize,
+        trigger: CalculationTrigger,
+        now: Moment,
+    ) -> Result<Vec<(SourceId, Option<(ValueType, Moment)>)>, OracleError> {
+        self.is_value_id_correct(value_id)?;
+
+        let observation = |asset: &ExternalValue<ValueType, Moment>| match (
+            asset.value,
+            asset.observed.or(asset.last_changed),
+        ) {
+            (Some(value), Some(observed)) => Some((value, observed)),
+            _ => None,
+        };
+
+        Ok(match (trigger, self.period_handler.get_part(now)) {
             // Calculate with prev period data
-            Part::Aggregate => self
-                .prev_period_source
+            (CalculationTrigger::Period, Part::Aggregate) => self
+                .prev_period_data
                 .iter()
-                .filter_map(|(_, assets)| {
+                .flat_map(|(_period, data)| data.iter())
+                .filter_map(|(source, assets)| {
                     assets
-                        .get(ex_asset_id)
+                        .get(value_id)
                         .and_then(|ex| ex.as_ref())
-                        .and_then(|asset| asset.value.as_ref())
+                        .map(|asset| (source.clone(), observation(asset)))
                 })
                 .collect(),
 
             // Calculate with current period data
-            Part::Calculate => self
+            _ => self
                 .sources
                 .iter()
-                .filter_map(|(_, assets)| {
-                    assets
-                        .get(ex_asset_id)
-                        .and_then(|asset| asset.value.as_ref())
+                .map(|(source, assets)| {
+                    (source.clone(), assets.get(value_id).and_then(observation))
                 })
                 .collect(),
         })
     }
 
+    fn get_actual_value_variants(
+        &self,
+        ex_asset_id: usize,
+        trigger: CalculationTrigger,
+        now: Moment,
+    ) -> Result<Vec<(SourceId, ValueType, Moment)>, OracleError> {
+        Ok(self
+            .get_source_observations(ex_asset_id, trigger, now)?
+            .into_iter()
+            .filter_map(|(source, observation)| {
+                observation.map(|(value, observed)| (source, value, observed))
+            })
+            .collect())
+    }
+
     pub fn pull_value(&mut self, ex_asset_id: usize) -> Result<(ValueType, Moment), OracleError> {
         self.is_value_id_correct(ex_asset_id)?;
 
@@ -303,10 +960,23 @@ impl<
         }
     }
 
+    /// Calculate value with equal votes of sources in majority aggregation
     pub fn calculate_value(
         &mut self,
         value_id: usize,
         now: Moment,
+    ) -> Result<ValueType, OracleError> {
+        self.calculate_staked_value(value_id, now, None)
+    }
+
+    /// Calculate value, votes in majority aggregation are weighted by `stakes` of sources
+    ///
+    /// `None` - votes are equal
+    pub fn calculate_staked_value(
+        &mut self,
+        value_id: usize,
+        now: Moment,
+        stakes: Option<&Stakes<SourceId>>,
     ) -> Result<ValueType, OracleError> {
         if !self.is_sources_enough() {
             return Err(OracleError::FewSources(
@@ -324,28 +994,142 @@ impl<
             return Err(OracleError::EmptyPushedValueInPeriod);
         }
 
-        let values: Vec<&ValueType> = self.get_actual_value_variants(value_id, now)?;
+        let trigger = self
+            .get_calculation_trigger(value_id, now)?
+            .unwrap_or(CalculationTrigger::Period);
+        let variants = self.get_actual_value_variants(value_id, trigger, now)?;
 
-        if self.source_limit as usize > values.len() {
+        if self.get_required_values() > variants.len() {
             return Err(OracleError::FewPushedValue(
-                self.source_limit as usize,
+                self.get_required_values(),
+                variants.len(),
+            ));
+        }
+
+        let observed = Self::get_median_moment(
+            variants
+                .iter()
+                .map(|(_source, _value, observed)| *observed)
+                .collect(),
+        )
+        .unwrap_or(now);
+        let dispersion = Dispersion::new(
+            variants
+                .iter()
+                .map(|(_source, value, _observed)| *value)
+                .collect(),
+        );
+        let values = variants
+            .into_iter()
+            .map(|(source, value, _observed)| (source, value))
+            .collect();
+
+        let current_period = self.period_handler.get_period_number(now);
+        self.aggregate(value_id, values, stakes).map(|res| {
+            let mut calculated = ExternalValue::default();
+            calculated.update_observed(res, now, observed);
+            let confirmed = match self.breakers[value_id].as_mut() {
+                Some(breaker) => breaker.check(calculated, self.values[value_id].value),
+                None => Some(calculated),
+            };
+            if let Some(confirmed) = confirmed {
+                self.values[value_id] = confirmed;
+            }
+            self.dispersions[value_id] = dispersion;
+            if let Some(heartbeat) = self.heartbeats[value_id].as_mut() {
+                heartbeat.beat(current_period);
+            }
+            res
+        })
+    }
+
+    /// Calculate value for past `period` with pushed `data` of the period stored in history
+    ///
+    /// Stored data of the value is consumed, so the value can be calculated once for period
+    pub fn calculate_past_value(
+        &self,
+        value_id: usize,
+        period: Moment,
+        data: Option<&mut PeriodData<SourceId, ValueType, Moment>>,
+        stakes: Option<&Stakes<SourceId>>,
+        now: Moment,
+    ) -> Result<ValueType, OracleError> {
+        self.is_value_id_correct(value_id)?;
+
+        let current_period = self.period_handler.get_period_number(now);
+        if period >= current_period {
+            return Err(OracleError::NotPastPeriod);
+        }
+        let data = data.ok_or(OracleError::PeriodDataNotFound)?;
+
+        let values: Vec<(SourceId, ValueType)> = data
+            .iter()
+            .filter_map(|(source, assets)| {
+                assets
+                    .get(value_id)
+                    .and_then(|ex| ex.as_ref())
+                    .and_then(|ex| ex.value)
+                    .map(|value| (source.clone(), value))
+            })
+            .collect();
+
+        if self.get_required_values() > values.len() {
+            return Err(OracleError::FewPushedValue(
+                self.get_required_values(),
                 values.len(),
             ));
         }
 
-        match get_median(values) {
-            Some(Median::Value(value)) => Ok(*value),
-            Some(Median::Pair(left, right)) => {
-                let sum = *left + *right;
-                let div = ValueType::one() + ValueType::one();
-                Ok(sum / div)
+        let res = self.aggregate(value_id, values, stakes)?;
+        data.values_mut()
+            .filter_map(|assets| assets.get_mut(value_id))
+            .for_each(|ex| *ex = None);
+        Ok(res)
+    }
+
+    /// Aggregate values pushed by sources
+    ///
+    /// Votes in majority aggregation are weighted by `stakes`, source without stake has no
+    /// weight. `None` - votes are weighted equally.
+    fn aggregate(
+        &self,
+        value_id: usize,
+        values: Vec<(SourceId, ValueType)>,
+        stakes: Option<&Stakes<SourceId>>,
+    ) -> Result<ValueType, OracleError> {
+        match self.get_aggregation(value_id) {
+            Aggregation::Median => {
+                Self::calculate_median(values.into_iter().map(|(_source, value)| value).collect())
+            }
+            Aggregation::Majority(threshold) => {
+                let votes = values
+                    .into_iter()
+                    .map(|(source, value)| {
+                        let weight =
+                            stakes.map_or(1, |stakes| stakes.get(&source).copied().unwrap_or(0));
+                        (value, weight)
+                    })
+                    .collect();
+
+                get_weighted_majority(votes, threshold).ok_or(OracleError::NoMajority)
             }
+            Aggregation::Hash(_) => Err(OracleError::PayloadValue(value_id)),
+        }
+    }
+
+    fn get_median_moment(moments: Vec<Moment>) -> Option<Moment> {
+        match get_median(moments)? {
+            Median::Value(moment) => Some(moment),
+            Median::Pair(left, right) => Some(left + (right - left) / Moment::from(2u32)),
+        }
+    }
+
+    fn calculate_median(values: Vec<ValueType>) -> Result<ValueType, OracleError> {
+        match get_median(values) {
+            Some(Median::Value(value)) => Ok(value),
+            Some(Median::Pair(left, right)) => Ok(average(left, right)),
             _ => Err(OracleError::CalculationError),
         }
-        .map(|res| {
-            self.values[value_id].update(res, now);
-            res
-        })
     }
 }
 
@@ -354,6 +1138,9 @@ mod tests {
     type Oracle = super::Oracle<u32, u32, u32, u32>;
     type PeriodHandler = super::PeriodHandler<u32>;
     type OE = super::OracleError;
+    use super::{Aggregation, BTreeMap, CalculationTrigger, SourceMode};
+    type Stakes = super::Stakes<u32>;
+    use sp_arithmetic::Perbill;
 
     const ALICE: u32 = 100;
     const BOB: u32 = 132;
@@ -410,6 +1197,45 @@ mod tests {
         assert_eq!(oracle.sources.len(), 0);
     }
 
+    #[test]
+    fn decimals() {
+        let mut oracle = create_oracle();
+        assert!(oracle.decimals.iter().all(|decimals| *decimals == 0));
+
+        assert_eq!(
+            oracle.set_decimals(vec![8]),
+            Err(OE::WrongValuesCount(get_assets_names().len(), 1))
+        );
+        assert_ok!(oracle.set_decimals(vec![8, 2, 0, 0, 0, 6]));
+        assert_eq!(oracle.decimals[5], 6);
+
+        oracle.add_assets(b"new".to_vec(), 4);
+        assert_eq!(oracle.decimals.len(), oracle.get_values_count());
+        assert_eq!(oracle.decimals[6], 4);
+    }
+
+    #[test]
+    fn signed_values() {
+        let mut oracle = super::Oracle::<u32, i64, u32, u32>::new(
+            b"funding".to_vec(),
+            TABLE_ID,
+            create_period_handler(),
+            SOURCE_LIMIT,
+            vec![b"rate".to_vec()],
+        );
+        oracle
+            .update_sources(ACCOUNTS.to_vec().into_iter())
+            .expect("Update accounts error.");
+
+        [(ALICE, -25), (BOB, -10), (CHUCK, 5), (CRAIG, -12)]
+            .iter()
+            .for_each(|(account, value)| {
+                assert_ok!(oracle.push_values(account, BEGIN, vec![*value].into_iter()));
+            });
+
+        assert_eq!(oracle.calculate_value(0, CALCULATE_BEGIN), Ok(-11));
+    }
+
     #[test]
     fn accounts() {
         let mut oracle = create_oracle();
@@ -428,6 +1254,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn fixed_sources() {
+        let mut oracle = create_oracle();
+        assert_eq!(oracle.get_source_mode(), SourceMode::Tablescore);
+
+        assert_eq!(
+            oracle.set_fixed_sources(vec![ALICE, BOB, BOB, CHUCK]),
+            Err(OE::FewSources(SOURCE_LIMIT as usize, 3))
+        );
+        assert_eq!(oracle.get_source_mode(), SourceMode::Tablescore);
+        assert!(oracle.is_sources_empty());
+
+        assert_ok!(oracle.set_fixed_sources(vec![ALICE, BOB, CHUCK, CRAIG]));
+        assert_eq!(oracle.get_source_mode(), SourceMode::Fixed);
+        assert!(oracle.is_source(&CRAIG));
+
+        oracle.set_tablescore_sources();
+        assert_eq!(oracle.get_source_mode(), SourceMode::Tablescore);
+    }
+
+    #[test]
+    fn initial_values() {
+        let mut oracle = create_oracle();
+
+        assert_eq!(
+            oracle.set_initial_values(vec![1], BEGIN),
+            Err(OE::WrongValuesCount(get_assets_names().len(), 1))
+        );
+        assert!(oracle.values.iter().all(|val| val.is_clean()));
+
+        assert_ok!(oracle.set_initial_values(get_assets_value(42), BEGIN));
+        assert!(oracle
+            .values
+            .iter()
+            .all(|val| val.get() == Some((42, BEGIN))));
+    }
+
     #[test]
     fn push_simple() {
         let mut oracle = create_oracle();
@@ -480,5 +1343,469 @@ mod tests {
 
         assert_eq!(oracle.calculate_value(0, CALCULATE_BEGIN), Ok(125));
         assert_eq!(oracle.calculate_value(5, CALCULATE_BEGIN), Ok(5476382));
+
+        assert_eq!(
+            oracle.get_source_values(0, CalculationTrigger::Period, CALCULATE_BEGIN),
+            Ok(vec![
+                (ALICE, Some(123)),
+                (BOB, Some(124)),
+                (CAROL, None),
+                (CHUCK, None),
+                (EVE, Some(126)),
+                (CRAIG, None),
+                (DAN, Some(128)),
+                (ERING, None),
+            ])
+        );
+    }
+
+    #[test]
+    fn deviation_trigger() {
+        let mut oracle = create_oracle();
+        oracle.set_deviation_threshold(Some(Perbill::from_percent(10)));
+
+        oracle
+            .update_sources(ACCOUNTS.to_vec().into_iter())
+            .expect("Update accounts error.");
+
+        [ALICE, BOB, CHUCK, CRAIG].iter().for_each(|account| {
+            assert_ok!(oracle.push_values(account, BEGIN, get_assets_value(100).into_iter()));
+        });
+        assert_eq!(oracle.calculate_value(0, CALCULATE_BEGIN), Ok(100));
+
+        let next_begin = BEGIN + PERIOD;
+        let push = |oracle: &mut Oracle, account, value| {
+            assert_ok!(oracle.push_values(
+                &account,
+                next_begin,
+                get_assets_value(value).into_iter()
+            ));
+        };
+
+        // Not enough values
+        push(&mut oracle, ALICE, 150);
+        push(&mut oracle, BOB, 100);
+        push(&mut oracle, CHUCK, 100);
+        assert_eq!(oracle.get_calculation_trigger(0, next_begin + 1), Ok(None));
+
+        // Median 102 is within threshold
+        push(&mut oracle, DAN, 105);
+        assert_eq!(oracle.get_calculation_trigger(0, next_begin + 1), Ok(None));
+
+        // Median 127 is out of threshold
+        push(&mut oracle, EVE, 150);
+        push(&mut oracle, CRAIG, 150);
+        assert_eq!(
+            oracle.get_calculation_trigger(0, next_begin + 2),
+            Ok(Some(CalculationTrigger::Deviation))
+        );
+        assert_eq!(oracle.calculate_value(0, next_begin + 2), Ok(127));
+        assert_eq!(oracle.get_calculation_trigger(0, next_begin + 3), Ok(None));
+
+        // Regular calculation in calculate part is still allowed
+        assert_eq!(
+            oracle.get_calculation_trigger(0, next_begin + AGGREGATE_PART + 1),
+            Ok(Some(CalculationTrigger::Period))
+        );
+    }
+
+    #[test]
+    fn heartbeat() {
+        let mut oracle = create_oracle();
+        assert_eq!(
+            oracle.set_heartbeat(100, Some(1), BEGIN),
+            Err(OE::WrongValueId(100))
+        );
+        assert_ok!(oracle.set_heartbeat(0, Some(1), BEGIN));
+
+        oracle
+            .update_sources(ACCOUNTS.to_vec().into_iter())
+            .expect("Update accounts error.");
+
+        [ALICE, BOB, CHUCK, CRAIG].iter().for_each(|account| {
+            assert_ok!(oracle.push_values(account, BEGIN, get_assets_value(100).into_iter()));
+        });
+        assert_eq!(oracle.calculate_value(0, CALCULATE_BEGIN), Ok(100));
+
+        let missed_at = BEGIN + 2 * PERIOD;
+        assert_eq!(oracle.check_heartbeats(missed_at - 1), vec![]);
+        assert!(!oracle.is_degraded(0, missed_at - 1));
+
+        assert!(oracle.is_degraded(0, missed_at));
+        assert_eq!(oracle.check_heartbeats(missed_at), vec![0]);
+        assert_eq!(oracle.check_heartbeats(missed_at), vec![]);
+        assert!(!oracle.is_degraded(1, missed_at));
+
+        [ALICE, BOB, CHUCK, CRAIG].iter().for_each(|account| {
+            assert_ok!(oracle.push_values(account, missed_at, get_assets_value(100).into_iter()));
+        });
+        assert_eq!(
+            oracle.calculate_value(0, missed_at + AGGREGATE_PART + 1),
+            Ok(100)
+        );
+        assert!(!oracle.is_degraded(0, missed_at + AGGREGATE_PART + 1));
+    }
+
+    #[test]
+    fn circuit_breaker() {
+        let mut oracle = create_oracle();
+        assert_eq!(
+            oracle.set_circuit_breaker(100, Some(Perbill::from_percent(10))),
+            Err(OE::WrongValueId(100))
+        );
+        assert_ok!(oracle.set_circuit_breaker(0, Some(Perbill::from_percent(10))));
+        assert_eq!(oracle.confirm_value(0), Err(OE::NotFrozenValue(0)));
+
+        oracle
+            .update_sources(ACCOUNTS.to_vec().into_iter())
+            .expect("Update accounts error.");
+
+        let push_and_calculate = |oracle: &mut Oracle, period: u32, value: u32| {
+            let begin = BEGIN + period * PERIOD;
+            [ALICE, BOB, CHUCK, CRAIG].iter().for_each(|account| {
+                assert_ok!(oracle.push_values(account, begin, get_assets_value(value).into_iter()));
+            });
+            assert_eq!(
+                oracle.calculate_value(0, begin + AGGREGATE_PART + 1),
+                Ok(value)
+            );
+        };
+
+        push_and_calculate(&mut oracle, 0, 100);
+        push_and_calculate(&mut oracle, 1, 110);
+        assert!(!oracle.is_frozen(0));
+
+        push_and_calculate(&mut oracle, 2, 200);
+        assert!(oracle.is_frozen(0));
+        let previous = Ok((110, BEGIN + PERIOD + AGGREGATE_PART + 1));
+        assert_eq!(oracle.pull_value(0), previous);
+
+        // Value stays frozen after calculation within threshold, the previous value is kept
+        push_and_calculate(&mut oracle, 3, 205);
+        assert!(oracle.is_frozen(0));
+        assert_eq!(oracle.pull_value(0), previous);
+        assert_eq!(
+            oracle.get_frozen_value(0).and_then(|frozen| frozen.value),
+            Some(205)
+        );
+
+        assert_ok!(oracle.confirm_value(0));
+        assert!(!oracle.is_frozen(0));
+        assert_eq!(
+            oracle.pull_value(0),
+            Ok((205, BEGIN + 3 * PERIOD + AGGREGATE_PART + 1))
+        );
+    }
+
+    #[test]
+    fn past_periods() {
+        let mut oracle = create_oracle();
+        assert_eq!(oracle.set_history_depth(0), Err(OE::WrongHistoryDepth(0)));
+        assert_eq!(
+            oracle.set_history_depth(super::MAX_HISTORY_DEPTH + 1),
+            Err(OE::WrongHistoryDepth(super::MAX_HISTORY_DEPTH + 1))
+        );
+        assert_ok!(oracle.set_history_depth(2));
+        assert_eq!(oracle.get_history_depth(), 2);
+
+        oracle
+            .update_sources(ACCOUNTS.to_vec().into_iter())
+            .expect("Update accounts error.");
+
+        // Nobody calculated values in periods 0, 1 and 2
+        let mut history = BTreeMap::new();
+        [(0, 100), (1, 110), (2, 120), (3, 130)]
+            .iter()
+            .for_each(|&(period, value)| {
+                if let Some(archived) = oracle.archive_pushed_data(period) {
+                    history.insert(archived.period, archived.data);
+                }
+                [ALICE, BOB, CHUCK, CRAIG].iter().for_each(|account| {
+                    assert_ok!(oracle.push_values(
+                        account,
+                        BEGIN + period * PERIOD,
+                        get_assets_value(value).into_iter()
+                    ));
+                });
+            });
+        assert_eq!(history.keys().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        let now = BEGIN + 3 * PERIOD;
+        assert_eq!(
+            oracle.calculate_past_value(0, 3, None, None, now),
+            Err(OE::NotPastPeriod)
+        );
+        assert_eq!(
+            oracle.calculate_past_value(0, 0, None, None, now),
+            Err(OE::PeriodDataNotFound)
+        );
+        assert_eq!(
+            oracle.calculate_past_value(0, 1, history.get_mut(&1), None, now),
+            Ok(110)
+        );
+        assert_eq!(
+            oracle.calculate_past_value(0, 2, history.get_mut(&2), None, now),
+            Ok(120)
+        );
+        assert_eq!(
+            oracle.calculate_past_value(0, 2, history.get_mut(&2), None, now),
+            Err(OE::FewPushedValue(SOURCE_LIMIT as usize, 0))
+        );
+        assert_eq!(
+            oracle.calculate_past_value(1, 2, history.get_mut(&2), None, now),
+            Ok(120)
+        );
+    }
+
+    #[test]
+    fn archive_pushes() {
+        let mut oracle = create_oracle();
+        oracle
+            .update_sources(ACCOUNTS.to_vec().into_iter())
+            .expect("Update accounts error.");
+        assert_eq!(oracle.archive_pushed_data(1), None);
+
+        [ALICE, BOB].iter().for_each(|account| {
+            assert_ok!(oracle.push_values(account, BEGIN, get_assets_value(100).into_iter()));
+        });
+        assert_eq!(oracle.archive_pushed_data(0), None);
+
+        let archived = oracle.archive_pushed_data(1).unwrap();
+        assert_eq!(archived.period, 0);
+        assert_eq!(archived.data.len(), ACCOUNTS.len());
+        assert_eq!(archived.pushes.len(), ACCOUNTS.len());
+        assert!(archived
+            .pushes
+            .iter()
+            .all(|(source, is_pushed)| *is_pushed == (*source == ALICE || *source == BOB)));
+
+        // Data is archived once, oracle keeps it for lazy calculation
+        assert_eq!(oracle.archive_pushed_data(1), None);
+        assert_eq!(
+            oracle.get_prev_period_data().map(|(period, _data)| *period),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn quorum() {
+        let mut oracle = create_oracle();
+        assert_eq!(oracle.get_required_values(), SOURCE_LIMIT as usize);
+        assert_eq!(
+            oracle.set_quorum(Some(Perbill::zero())),
+            Err(OE::WrongQuorum)
+        );
+        assert_ok!(oracle.set_quorum(Some(Perbill::from_rational_approximation(2u32, 3))));
+
+        oracle
+            .update_sources(ACCOUNTS.to_vec().into_iter())
+            .expect("Update accounts error.");
+        assert_eq!(oracle.get_required_values(), 6);
+
+        ACCOUNTS[..5].iter().for_each(|account| {
+            assert_ok!(oracle.push_values(account, BEGIN, get_assets_value(100).into_iter()));
+        });
+        assert_eq!(
+            oracle.calculate_value(0, CALCULATE_BEGIN),
+            Err(OE::FewPushedValue(6, 5))
+        );
+
+        assert_ok!(oracle.push_values(&ACCOUNTS[5], BEGIN, get_assets_value(100).into_iter()));
+        assert_eq!(oracle.calculate_value(0, CALCULATE_BEGIN), Ok(100));
+
+        assert_ok!(oracle.set_quorum(Some(Perbill::one())));
+        assert_eq!(oracle.get_required_values(), ACCOUNTS.len());
+    }
+
+    #[test]
+    fn pause_resume() {
+        let mut oracle = create_oracle();
+        assert_ok!(oracle.set_heartbeat(0, Some(1), BEGIN));
+
+        oracle
+            .update_sources(ACCOUNTS.to_vec().into_iter())
+            .expect("Update accounts error.");
+
+        [ALICE, BOB, CHUCK, CRAIG].iter().for_each(|account| {
+            assert_ok!(oracle.push_values(account, BEGIN, get_assets_value(100).into_iter()));
+        });
+
+        oracle.pause();
+        assert!(oracle.is_paused());
+
+        let resumed_at = BEGIN + 10 * PERIOD + 3;
+        assert_eq!(oracle.check_heartbeats(resumed_at), vec![]);
+        assert!(!oracle.is_degraded(0, resumed_at));
+
+        oracle.resume(resumed_at);
+        assert!(!oracle.is_paused());
+        assert!(!oracle.is_degraded(0, resumed_at));
+
+        // Data pushed before pause is dropped
+        assert_eq!(
+            oracle.calculate_value(0, resumed_at),
+            Err(OE::EmptyPushedValueInPeriod)
+        );
+
+        [ALICE, BOB, CHUCK, CRAIG].iter().for_each(|account| {
+            assert_ok!(oracle.push_values(account, resumed_at, get_assets_value(200).into_iter()));
+        });
+        assert_eq!(
+            oracle.calculate_value(1, resumed_at + AGGREGATE_PART + 1),
+            Ok(200)
+        );
+    }
+
+    #[test]
+    fn majority() {
+        let mut oracle = create_oracle();
+        assert_eq!(oracle.get_aggregation(0), Aggregation::Median);
+        assert_eq!(
+            oracle.set_aggregation(6, Aggregation::Majority(Perbill::from_percent(50))),
+            Err(OE::WrongValueId(6))
+        );
+        assert_ok!(oracle.set_aggregation(0, Aggregation::Majority(Perbill::from_percent(50))));
+
+        oracle
+            .update_sources(ACCOUNTS.to_vec().into_iter())
+            .expect("Update accounts error.");
+
+        [(ALICE, 1), (BOB, 1), (CHUCK, 1), (CRAIG, 0)]
+            .iter()
+            .for_each(|(account, value)| {
+                assert_ok!(oracle.push_values(
+                    account,
+                    BEGIN,
+                    get_assets_value(*value).into_iter()
+                ));
+            });
+
+        // Without stakes votes are equal
+        assert_eq!(oracle.calculate_value(0, CALCULATE_BEGIN), Ok(1));
+        assert_eq!(oracle.get_value_deviation(0, 0, 1), Perbill::one());
+        assert_eq!(oracle.get_value_deviation(0, 1, 1), Perbill::zero());
+        assert_eq!(
+            oracle.get_value_deviation(1, 90, 100),
+            Perbill::from_percent(10)
+        );
+
+        let stakes = |stakes: &[(u32, u128)]| stakes.iter().copied().collect::<Stakes>();
+        let calculate = |oracle: &mut Oracle, stakes: &Stakes| {
+            oracle.calculate_staked_value(0, CALCULATE_BEGIN, Some(stakes))
+        };
+        let mut all_staked = stakes(&[(ALICE, 100), (BOB, 100), (CHUCK, 100), (CRAIG, 500)]);
+        assert_eq!(calculate(&mut oracle, &all_staked), Ok(0));
+
+        all_staked.insert(CRAIG, 300);
+        assert_eq!(calculate(&mut oracle, &all_staked), Err(OE::NoMajority));
+
+        // Votes of sources without stake have no weight
+        assert_eq!(
+            calculate(&mut oracle, &stakes(&[(ALICE, 100), (CRAIG, 500)])),
+            Ok(0)
+        );
+        assert_eq!(calculate(&mut oracle, &stakes(&[])), Err(OE::NoMajority));
+
+        assert_ok!(oracle.set_aggregation(0, Aggregation::Majority(Perbill::from_percent(90))));
+        assert_eq!(
+            calculate(
+                &mut oracle,
+                &stakes(&[(ALICE, 100), (BOB, 100), (CRAIG, 100)])
+            ),
+            Err(OE::NoMajority)
+        );
+    }
+
+    #[test]
+    fn payload_value() {
+        let mut oracle = create_oracle();
+        assert_ok!(oracle.set_aggregation(0, Aggregation::Hash(Perbill::from_percent(60))));
+
+        oracle
+            .update_sources(ACCOUNTS.to_vec().into_iter())
+            .expect("Update accounts error.");
+        assert_eq!(oracle.get_required_sources(Perbill::from_percent(60)), 5);
+        assert_eq!(oracle.get_required_sources(Perbill::zero()), 1);
+
+        ACCOUNTS.iter().for_each(|account| {
+            assert_ok!(oracle.push_values(account, BEGIN, get_assets_value(100).into_iter()));
+        });
+
+        assert_eq!(oracle.get_calculation_trigger(0, CALCULATE_BEGIN), Ok(None));
+        assert_eq!(
+            oracle.calculate_value(0, CALCULATE_BEGIN),
+            Err(OE::PayloadValue(0))
+        );
+        assert_eq!(oracle.calculate_value(1, CALCULATE_BEGIN), Ok(100));
+    }
+
+    #[test]
+    fn observation() {
+        let mut oracle = create_oracle();
+        oracle
+            .update_sources(ACCOUNTS.to_vec().into_iter())
+            .expect("Update accounts error.");
+
+        assert_eq!(
+            oracle.push_observed_values(&ALICE, BEGIN, BEGIN - 1, get_assets_value(10).into_iter()),
+            Err(OE::WrongObservationTime)
+        );
+
+        oracle.set_observation_tolerance(3);
+        assert_eq!(
+            oracle.push_observed_values(&ALICE, BEGIN, BEGIN + 4, get_assets_value(10).into_iter()),
+            Err(OE::WrongObservationTime)
+        );
+
+        [
+            (ALICE, BEGIN - 1),
+            (BOB, BEGIN - 3),
+            (CHUCK, BEGIN),
+            (CRAIG, BEGIN + 3),
+        ]
+        .iter()
+        .for_each(|(account, observed)| {
+            assert_ok!(oracle.push_observed_values(
+                account,
+                BEGIN,
+                *observed,
+                get_assets_value(10).into_iter()
+            ));
+        });
+
+        assert_eq!(oracle.calculate_value(0, CALCULATE_BEGIN), Ok(10));
+        assert_eq!(oracle.values[0].last_changed, Some(CALCULATE_BEGIN));
+        assert_eq!(oracle.values[0].observed, Some(BEGIN - 1));
+    }
+
+    #[test]
+    fn dispersion() {
+        let mut oracle = create_oracle();
+        oracle
+            .update_sources(ACCOUNTS.to_vec().into_iter())
+            .expect("Update accounts error.");
+        assert_eq!(oracle.get_dispersion(0), None);
+
+        [(ALICE, 110), (BOB, 90), (CHUCK, 130), (CRAIG, 100)]
+            .iter()
+            .for_each(|(account, value)| {
+                assert_ok!(oracle.push_values(
+                    account,
+                    BEGIN,
+                    get_assets_value(*value).into_iter()
+                ));
+            });
+
+        assert_eq!(oracle.calculate_value(0, CALCULATE_BEGIN), Ok(105));
+        assert_eq!(
+            oracle.get_dispersion(0),
+            Some(&super::Dispersion {
+                sources: 4,
+                min: 90,
+                max: 130,
+                iqr: 25,
+            })
+        );
+        assert_eq!(oracle.get_dispersion(1), None);
     }
 }
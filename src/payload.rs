@@ -0,0 +1,124 @@
+use codec::{Decode, Encode};
+use rstd::collections::btree_map::BTreeMap;
+use rstd::prelude::Vec;
+
+/// Max length of payload pushed by source
+pub const MAX_PAYLOAD_LEN: usize = 1024;
+
+/// Byte payload value, finalised when enough sources push payloads with the same hash
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct PayloadValue<Hash: Ord, Moment, SourceId: Ord> {
+    /// Finalised payload, `None` if consensus was never reached
+    pub payload: Option<Vec<u8>>,
+
+    /// Hash of finalised payload
+    pub hash: Option<Hash>,
+
+    /// Moment we last finalised the payload
+    pub last_changed: Option<Moment>,
+
+    /// Period of pushed payloads
+    period: Option<Moment>,
+
+    /// Hashes of payloads pushed by sources in period
+    hashes: BTreeMap<SourceId, Hash>,
+
+    /// Payloads pushed in period by their hashes
+    candidates: BTreeMap<Hash, Vec<u8>>,
+
+    /// Payload was finalised in period
+    is_finalized: bool,
+}
+
+impl<Hash: Ord + Clone, Moment: Eq + Copy, SourceId: Ord> PayloadValue<Hash, Moment, SourceId> {
+    /// Push payload of source in `period`, the previous push of source in period is replaced
+    ///
+    /// Payload is finalised, when its hash is pushed by `required` sources.
+    /// Only the first agreed payload is finalised in period.
+    /// Return the hash only when payload became finalised.
+    pub fn push(
+        &mut self,
+        source: SourceId,
+        hash: Hash,
+        payload: Vec<u8>,
+        period: Moment,
+        now: Moment,
+        required: usize,
+    ) -> Option<Hash> {
+        if self.period != Some(period) {
+            self.period = Some(period);
+            self.hashes.clear();
+            self.candidates.clear();
+            self.is_finalized = false;
+        }
+
+        if let Some(replaced) = self.hashes.insert(source, hash.clone()) {
+            if !self.hashes.values().any(|pushed| *pushed == replaced) {
+                self.candidates.remove(&replaced);
+            }
+        }
+        self.candidates.entry(hash.clone()).or_insert(payload);
+
+        if self.is_finalized || self.get_votes(&hash) < required.max(1) {
+            return None;
+        }
+
+        self.is_finalized = true;
+        self.payload = self.candidates.get(&hash).cloned();
+        self.hash = Some(hash.clone());
+        self.last_changed = Some(now);
+        Some(hash)
+    }
+
+    /// Source pushed payload in `period`
+    pub fn is_pushed_by(&self, source: &SourceId, period: Moment) -> bool {
+        self.period == Some(period) && self.hashes.contains_key(source)
+    }
+
+    /// Count of sources, which pushed payload with `hash` in current period
+    pub fn get_votes(&self, hash: &Hash) -> usize {
+        self.hashes
+            .values()
+            .filter(|pushed| *pushed == hash)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    type PayloadValue = super::PayloadValue<u8, u32, u32>;
+
+    #[test]
+    fn push() {
+        let mut value = PayloadValue::default();
+
+        assert_eq!(value.push(1, 10, b"ten".to_vec(), 0, 5, 2), None);
+        assert_eq!(value.push(2, 20, b"twenty".to_vec(), 0, 6, 2), None);
+        assert_eq!(value.payload, None);
+
+        // Source changes its payload
+        assert_eq!(value.push(2, 10, b"ten".to_vec(), 0, 7, 2), Some(10));
+        assert_eq!(value.payload, Some(b"ten".to_vec()));
+        assert_eq!(value.last_changed, Some(7));
+        assert_eq!(value.get_votes(&20), 0);
+        assert!(value.candidates.get(&20).is_none());
+        assert!(value.is_pushed_by(&2, 0));
+        assert!(!value.is_pushed_by(&3, 0));
+
+        // Already finalised in period
+        assert_eq!(value.push(3, 10, b"ten".to_vec(), 0, 8, 2), None);
+        assert_eq!(value.push(1, 30, b"thirty".to_vec(), 0, 8, 1), None);
+        assert_eq!(value.get_votes(&10), 2);
+        assert_eq!(value.hash, Some(10));
+
+        // Votes of past period are dropped
+        assert_eq!(value.push(1, 20, b"twenty".to_vec(), 1, 15, 2), None);
+        assert_eq!(value.get_votes(&10), 0);
+        assert!(!value.is_pushed_by(&2, 0));
+        assert_eq!(value.payload, Some(b"ten".to_vec()));
+        assert_eq!(value.push(3, 20, b"twenty".to_vec(), 1, 16, 2), Some(20));
+        assert_eq!(value.payload, Some(b"twenty".to_vec()));
+        assert_eq!(value.hash, Some(20));
+    }
+}
@@ -0,0 +1,122 @@
+use codec::{Decode, Encode};
+use sp_arithmetic::Perbill;
+
+/// Handler of penalized sources
+///
+/// Can be used for drop source score in tablescore
+pub trait OnSourcePenalty<OracleId, AccountId> {
+    fn on_source_penalty(oracle_id: OracleId, source: &AccountId);
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<OracleId: Clone, AccountId> OnSourcePenalty<OracleId, AccountId> for Tuple {
+    fn on_source_penalty(oracle_id: OracleId, source: &AccountId) {
+        for_tuples!( #( Tuple::on_source_penalty(oracle_id.clone(), source); )* );
+    }
+}
+
+/// Penalty settings of oracle
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct PenaltyConfig<Balance> {
+    /// Max deviation of pushed value from calculated one
+    pub tolerance: Perbill,
+
+    /// Misses count, after which source is penalized. Zero - without limit
+    pub max_misses: u32,
+
+    /// Deviations count, after which source is penalized. Zero - without limit
+    pub max_deviations: u32,
+
+    /// Amount slashed from source bond in penalty
+    pub slash_amount: Balance,
+}
+
+/// Penalty tracking of source in oracle
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct SourcePenalty {
+    /// Count of periods, in which the source didn't push any value
+    pub misses: u32,
+
+    /// Count of pushed values out of tolerance of calculated ones
+    pub deviations: u32,
+}
+
+impl SourcePenalty {
+    /// Track period, in which the source didn't push
+    pub fn track_miss(&mut self) {
+        self.misses = self.misses.saturating_add(1);
+    }
+
+    /// Track deviation of value pushed by source from the calculated one
+    pub fn track_deviation(&mut self, deviation: Perbill, tolerance: Perbill) {
+        if deviation > tolerance {
+            self.deviations = self.deviations.saturating_add(1);
+        }
+    }
+
+    pub fn is_limit_exceeded<Balance>(&self, config: &PenaltyConfig<Balance>) -> bool {
+        (config.max_misses != 0 && self.misses >= config.max_misses)
+            || (config.max_deviations != 0 && self.deviations >= config.max_deviations)
+    }
+}
+
+/// Reserved by source amount, that can be slashed in penalty
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Bond<AssetId, Balance> {
+    pub asset_id: AssetId,
+    pub amount: Balance,
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_arithmetic::Perbill;
+
+    type PenaltyConfig = super::PenaltyConfig<u64>;
+    type SourcePenalty = super::SourcePenalty;
+
+    fn create_config(max_misses: u32, max_deviations: u32) -> PenaltyConfig {
+        PenaltyConfig {
+            tolerance: Perbill::from_percent(10),
+            max_misses,
+            max_deviations,
+            slash_amount: 100,
+        }
+    }
+
+    #[test]
+    fn track() {
+        let tolerance = Perbill::from_percent(10);
+        let mut penalty = SourcePenalty::default();
+
+        penalty.track_deviation(Perbill::from_percent(5), tolerance);
+        penalty.track_deviation(Perbill::from_percent(10), tolerance);
+        assert_eq!(penalty, SourcePenalty::default());
+
+        penalty.track_miss();
+        penalty.track_deviation(Perbill::from_percent(11), tolerance);
+        penalty.track_deviation(Perbill::from_percent(20), tolerance);
+        assert_eq!(
+            penalty,
+            SourcePenalty {
+                misses: 1,
+                deviations: 2
+            }
+        );
+    }
+
+    #[test]
+    fn is_limit_exceeded() {
+        let penalty = SourcePenalty {
+            misses: 2,
+            deviations: 3,
+        };
+
+        assert!(!penalty.is_limit_exceeded(&create_config(0, 0)));
+        assert!(!penalty.is_limit_exceeded(&create_config(3, 4)));
+        assert!(penalty.is_limit_exceeded(&create_config(2, 0)));
+        assert!(penalty.is_limit_exceeded(&create_config(0, 3)));
+    }
+}
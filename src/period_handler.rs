@@ -1,7 +1,13 @@
 use codec::{Decode, Encode};
 use rstd::cmp::Ordering;
+use rstd::mem;
 use sp_arithmetic::traits::SimpleArithmetic;
 
+use crate::clock::ClockSource;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
 /// Period Handler
 /// |---------------------|---------------------|
 /// |       period        |       period        |
@@ -10,20 +16,43 @@ use sp_arithmetic::traits::SimpleArithmetic;
 /// agg - Part of timeline when we aggregate new data from sources
 /// calc - Part of timeline when we calculate aggregated values
 /// Calculate value we can only once at calc period or at next agg period
+///
+/// Period parameters can be changed only at period boundary, period numbers continue
+/// through the change
 #[derive(Encode, Decode, Clone, Eq, Default, PartialEq)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct PeriodHandler<Moment> {
-    /// Begin of period handle
+    /// Clock, that measures moments of periods
+    clock: ClockSource,
+
+    /// Periods in effect
+    current: Periods<Moment>,
+
+    /// Periods before the last change, to handle moments of in-flight data
+    previous: Option<Periods<Moment>>,
+
+    /// Periods, that will take effect at the next boundary
+    scheduled: Option<Periods<Moment>>,
+
+    /// Moment when we last update sources
+    last_sources_update: Option<Moment>,
+}
+
+/// Parameters of periods starting from `begin`
+#[derive(Encode, Decode, Clone, Copy, Eq, Default, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Periods<Moment> {
+    /// Begin of the first period
     begin: Moment,
 
+    /// Number of the first period
+    first_number: Moment,
+
     /// One period delta
     period: Moment,
 
     /// Aggregate part of period
     aggregate_part: Moment,
-
-    /// Moment when we last update sources
-    last_sources_update: Option<Moment>,
 }
 
 impl<Moment: Default + PartialOrd<Moment>> PeriodHandler<Moment> {
@@ -31,12 +60,28 @@ impl<Moment: Default + PartialOrd<Moment>> PeriodHandler<Moment> {
         now: Moment,
         period: Moment,
         aggregate_part: Moment,
+    ) -> Result<PeriodHandler<Moment>, ()> {
+        Self::with_clock(ClockSource::default(), now, period, aggregate_part)
+    }
+
+    /// Create period handler, that measures moments by `clock`
+    pub fn with_clock(
+        clock: ClockSource,
+        now: Moment,
+        period: Moment,
+        aggregate_part: Moment,
     ) -> Result<PeriodHandler<Moment>, ()> {
         if period > aggregate_part {
             Ok(PeriodHandler {
-                period,
-                aggregate_part,
-                begin: now,
+                clock,
+                current: Periods {
+                    begin: now,
+                    first_number: Moment::default(),
+                    period,
+                    aggregate_part,
+                },
+                previous: None,
+                scheduled: None,
                 last_sources_update: None,
             })
         } else {
@@ -45,31 +90,187 @@ impl<Moment: Default + PartialOrd<Moment>> PeriodHandler<Moment> {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Alignment of period starts
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub enum PeriodAlignment<Moment> {
+    /// Periods start from creation moment
+    Creation,
+    /// Periods start at multiples of the period (e.g. every full 10 minutes)
+    Period,
+    /// Periods start at `anchor + k * period`
+    Anchor(Moment),
+}
+
+impl<Moment> Default for PeriodAlignment<Moment> {
+    fn default() -> Self {
+        PeriodAlignment::Creation
+    }
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
 pub enum Part {
     Aggregate,
     Calculate,
 }
 
-impl<Moment: SimpleArithmetic + Copy> PeriodHandler<Moment> {
-    /// Get period number
-    pub fn get_period_number(&self, now: Moment) -> Moment {
-        (now - self.begin) / self.period
+impl<Moment: Copy> PeriodHandler<Moment> {
+    pub fn get_clock(&self) -> ClockSource {
+        self.clock
+    }
+}
+
+impl<Moment: SimpleArithmetic + Copy> Periods<Moment> {
+    fn get_period_number(&self, now: Moment) -> Moment {
+        self.first_number + (now - self.begin) / self.period
     }
 
     fn get_rest_of_period(&self, now: Moment) -> Moment {
-        let next_period = self.get_period_number(now) + Moment::one();
+        let next_period = (now - self.begin) / self.period + Moment::one();
         let next_period_begin = self.begin + (next_period * self.period);
         next_period_begin - now
     }
 
-    pub fn get_part(&self, now: Moment) -> Part {
+    fn get_part(&self, now: Moment) -> Part {
         if self.period - self.get_rest_of_period(now) <= self.aggregate_part {
             Part::Aggregate
         } else {
             Part::Calculate
         }
     }
+}
+
+impl<Moment: SimpleArithmetic + Copy> PeriodHandler<Moment> {
+    /// Create period handler, that starts periods according to `alignment`
+    ///
+    /// Begin is the start of the aligned period, that contains `now`
+    pub fn with_alignment(
+        clock: ClockSource,
+        alignment: PeriodAlignment<Moment>,
+        now: Moment,
+        period: Moment,
+        aggregate_part: Moment,
+    ) -> Result<PeriodHandler<Moment>, ()> {
+        let mut handler = Self::with_clock(clock, now, period, aggregate_part)?;
+        handler.current.begin = match alignment {
+            PeriodAlignment::Creation => now,
+            PeriodAlignment::Period => now - now % period,
+            PeriodAlignment::Anchor(anchor) if anchor <= now => now - (now - anchor) % period,
+            PeriodAlignment::Anchor(anchor) => {
+                let rest = (anchor - now) % period;
+                if rest.is_zero() {
+                    now
+                } else {
+                    now.checked_sub(&(period - rest)).ok_or(())?
+                }
+            }
+        };
+        Ok(handler)
+    }
+
+    /// Periods in effect at `now`
+    ///
+    /// Return `None` for moments before the known history of changes
+    fn get_periods(&self, now: Moment) -> Option<&Periods<Moment>> {
+        match (&self.scheduled, &self.previous) {
+            (Some(scheduled), _) if scheduled.begin <= now => Some(scheduled),
+            _ if self.current.begin <= now => Some(&self.current),
+            (_, Some(previous)) if previous.begin <= now => Some(previous),
+            _ => None,
+        }
+    }
+
+    pub fn get_period(&self, now: Moment) -> Moment {
+        self.get_periods(now).unwrap_or(&self.current).period
+    }
+
+    pub fn get_aggregate_part(&self, now: Moment) -> Moment {
+        self.get_periods(now)
+            .unwrap_or(&self.current)
+            .aggregate_part
+    }
+
+    /// Get period number
+    ///
+    /// Moments before the known history belong to the period preceding it
+    pub fn get_period_number(&self, now: Moment) -> Moment {
+        match self.get_periods(now) {
+            Some(periods) => periods.get_period_number(now),
+            None => self
+                .previous
+                .as_ref()
+                .unwrap_or(&self.current)
+                .first_number
+                .saturating_sub(Moment::one()),
+        }
+    }
+
+    /// Get part of period
+    ///
+    /// Moments before the known history are considered as calculate part
+    pub fn get_part(&self, now: Moment) -> Part {
+        self.get_periods(now)
+            .map_or(Part::Calculate, |periods| periods.get_part(now))
+    }
+
+    /// Start new period at `now`, period numbers continue after the current one
+    ///
+    /// Scheduled change takes effect immediately
+    pub fn restart(&mut self, now: Moment) {
+        let first_number = self.get_period_number(now) + Moment::one();
+        let periods = match self.scheduled.take() {
+            Some(scheduled) if scheduled.begin <= now => {
+                self.current = scheduled;
+                scheduled
+            }
+            Some(scheduled) => scheduled,
+            None => self.current,
+        };
+
+        self.previous = Some(mem::replace(
+            &mut self.current,
+            Periods {
+                begin: now,
+                first_number,
+                ..periods
+            },
+        ));
+    }
+
+    /// Change `period` and `aggregate_part` from the next period boundary
+    ///
+    /// Replaces the change, that was scheduled before and didn't take effect yet.
+    /// Return begin of the first changed period
+    pub fn schedule_change(
+        &mut self,
+        now: Moment,
+        period: Moment,
+        aggregate_part: Moment,
+    ) -> Result<Moment, ()> {
+        if period <= aggregate_part {
+            return Err(());
+        }
+
+        if let Some(scheduled) = self.scheduled.take() {
+            if scheduled.begin <= now {
+                self.previous = Some(mem::replace(&mut self.current, scheduled));
+            }
+        }
+
+        let first_number = self.current.get_period_number(now) + Moment::one();
+        let begin =
+            self.current.begin + (first_number - self.current.first_number) * self.current.period;
+
+        self.scheduled = Some(Periods {
+            begin,
+            first_number,
+            period,
+            aggregate_part,
+        });
+
+        Ok(begin)
+    }
 
     pub fn is_allow_aggregate(&self, now: Moment) -> bool {
         self.get_part(now) == Part::Aggregate
@@ -129,6 +330,9 @@ impl<Moment: SimpleArithmetic + Copy> PeriodHandler<Moment> {
 
 #[cfg(test)]
 mod tests {
+    use super::Part;
+    use crate::clock::ClockSource;
+
     type PeriodHandler = super::PeriodHandler<u32>;
 
     #[test]
@@ -137,6 +341,127 @@ mod tests {
 
         let handler = PeriodHandler::new(0, 100, 90);
         assert!(handler.is_ok());
+        assert_eq!(handler.unwrap().get_clock(), ClockSource::Timestamp);
+
+        let handler = PeriodHandler::with_clock(ClockSource::BlockNumber, 0, 100, 90);
+        assert_eq!(handler.unwrap().get_clock(), ClockSource::BlockNumber);
+    }
+
+    #[test]
+    fn alignment() {
+        use super::PeriodAlignment;
+
+        let aligned = |alignment, now| {
+            PeriodHandler::with_alignment(ClockSource::Timestamp, alignment, now, 100, 90)
+                .expect("Error in create period handler")
+        };
+
+        let handler = aligned(PeriodAlignment::Creation, 1234);
+        assert_eq!(handler.get_period_number(1234), 0);
+        assert_eq!(handler.get_period_number(1334), 1);
+
+        let handler = aligned(PeriodAlignment::Period, 1234);
+        assert_eq!(handler.get_period_number(1299), 0);
+        assert_eq!(handler.get_period_number(1300), 1);
+        assert!(handler.is_allow_aggregate(1290));
+        assert!(!handler.is_allow_aggregate(1291));
+
+        let handler = aligned(PeriodAlignment::Anchor(1050), 1234);
+        assert_eq!(handler.get_period_number(1249), 0);
+        assert_eq!(handler.get_period_number(1250), 1);
+
+        let handler = aligned(PeriodAlignment::Anchor(1510), 1234);
+        assert_eq!(handler.get_period_number(1309), 0);
+        assert_eq!(handler.get_period_number(1310), 1);
+        assert_eq!(handler.get_period_number(1510), 3);
+
+        assert_eq!(
+            PeriodHandler::with_alignment(
+                ClockSource::Timestamp,
+                PeriodAlignment::Anchor(1515),
+                10,
+                100,
+                90
+            ),
+            Err(())
+        );
+    }
+
+    #[test]
+    fn schedule_change() {
+        let mut handler = PeriodHandler::new(100, 100, 90).expect("Error in create period handler");
+
+        assert_eq!(handler.schedule_change(150, 100, 100), Err(()));
+        assert_eq!(handler.schedule_change(150, 50, 20), Ok(200));
+
+        (100..=199).for_each(|now| assert_eq!(handler.get_period_number(now), 0));
+        (200..=249).for_each(|now| assert_eq!(handler.get_period_number(now), 1));
+        (250..=299).for_each(|now| assert_eq!(handler.get_period_number(now), 2));
+        assert_eq!(handler.get_period(199), 100);
+        assert_eq!(handler.get_period(200), 50);
+
+        (200..=220).for_each(|now| assert!(handler.is_allow_aggregate(now)));
+        (221..=249).for_each(|now| assert!(!handler.is_allow_aggregate(now)));
+
+        // Reschedule before the change took effect
+        assert_eq!(handler.schedule_change(199, 200, 100), Ok(200));
+        (200..=399).for_each(|now| assert_eq!(handler.get_period_number(now), 1));
+
+        // Next change after the previous one took effect
+        assert_eq!(handler.schedule_change(450, 10, 5), Ok(600));
+        assert_eq!(handler.get_period_number(150), 0);
+        assert_eq!(handler.get_period_number(599), 2);
+        assert_eq!(handler.get_period_number(600), 3);
+        assert_eq!(handler.get_period_number(615), 4);
+
+        // Moments before the known history
+        assert_eq!(handler.schedule_change(700, 20, 10), Ok(710));
+        assert_eq!(handler.get_period_number(150), 0);
+        assert_eq!(handler.get_part(150), Part::Calculate);
+        assert_eq!(handler.get_period_number(700), 13);
+    }
+
+    #[test]
+    fn restart() {
+        let mut handler = PeriodHandler::new(100, 100, 90).expect("Error in create period handler");
+
+        handler.restart(495);
+        assert_eq!(handler.get_period_number(399), 2);
+        assert_eq!(handler.get_period_number(494), 3);
+        (495..=594).for_each(|now| assert_eq!(handler.get_period_number(now), 4));
+        (495..=585).for_each(|now| assert!(handler.is_allow_aggregate(now)));
+        assert!(!handler.is_allow_aggregate(586));
+
+        // Calculated before restart in calculate part of the same period
+        assert!(!handler.is_allow_calculate(Some(492), 500));
+        assert!(handler.is_allow_calculate(Some(492), 586));
+
+        assert_eq!(handler.schedule_change(550, 10, 5), Ok(595));
+        handler.restart(520);
+        assert_eq!(handler.get_period_number(519), 4);
+        assert_eq!(handler.get_period_number(520), 5);
+        assert_eq!(handler.get_period_number(530), 6);
+        assert_eq!(handler.get_period(520), 10);
+    }
+
+    #[test]
+    fn calculate_through_change() {
+        let mut handler = PeriodHandler::new(100, 100, 90).expect("Error in create period handler");
+        assert_eq!(handler.schedule_change(150, 50, 20), Ok(200));
+
+        // Calculated in calculate part before the change
+        assert!(!handler.is_allow_calculate(Some(195), 210));
+        assert!(handler.is_allow_calculate(Some(195), 221));
+        assert!(handler.is_allow_calculate(Some(195), 250));
+
+        // Not calculated in the last period before the change
+        assert!(handler.is_allow_calculate(Some(150), 210));
+        assert!(handler.is_allow_calculate(None, 210));
+
+        // Calculated after the change in the first changed period
+        assert!(!handler.is_allow_calculate(Some(221), 240));
+        assert!(!handler.is_allow_calculate(Some(221), 260));
+        assert!(handler.is_allow_calculate(Some(221), 271));
     }
 
     #[test]
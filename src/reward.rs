@@ -0,0 +1,98 @@
+use codec::{Decode, Encode};
+use sp_arithmetic::traits::SimpleArithmetic;
+use sp_arithmetic::Perbill;
+
+/// Pool of rewards for sources of one oracle
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct RewardPool<AssetId, Balance> {
+    /// Asset in which rewards are paid
+    pub asset_id: AssetId,
+
+    /// Amount distributed among accurate sources in each period
+    pub period_reward: Balance,
+
+    /// Max deviation of pushed value from calculated one to get reward
+    pub tolerance: Perbill,
+
+    /// Funds left in pool
+    pub balance: Balance,
+
+    /// Rewards taken out of pool, but not claimed by sources yet
+    pub unclaimed: Balance,
+}
+
+impl<AssetId, Balance: SimpleArithmetic + Copy> RewardPool<AssetId, Balance> {
+    pub fn new(asset_id: AssetId, period_reward: Balance, tolerance: Perbill) -> Self {
+        RewardPool {
+            asset_id,
+            period_reward,
+            tolerance,
+            balance: Balance::zero(),
+            unclaimed: Balance::zero(),
+        }
+    }
+
+    /// Pool has neither funds nor unclaimed rewards, so its asset can be changed
+    pub fn is_empty(&self) -> bool {
+        self.balance.is_zero() && self.unclaimed.is_zero()
+    }
+
+    /// Account reward claimed by source
+    pub fn claim(&mut self, reward: Balance) {
+        self.unclaimed = self.unclaimed.saturating_sub(reward);
+    }
+
+    /// Take reward for one calculated value out of pool
+    ///
+    /// Period reward is shared equally between `values_count` oracle values, and the value
+    /// reward is shared equally between `sources_count` accurate sources.
+    ///
+    /// Return reward for each source
+    pub fn take_value_reward(&mut self, values_count: usize, sources_count: usize) -> Balance {
+        if values_count == 0 || sources_count == 0 {
+            return Balance::zero();
+        }
+
+        let value_reward = rstd::cmp::min(
+            self.period_reward / Balance::from(values_count as u32),
+            self.balance,
+        );
+        let source_reward = value_reward / Balance::from(sources_count as u32);
+
+        let taken = source_reward * Balance::from(sources_count as u32);
+        self.balance -= taken;
+        self.unclaimed = self.unclaimed.saturating_add(taken);
+        source_reward
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_arithmetic::Perbill;
+
+    type RewardPool = super::RewardPool<u32, u64>;
+
+    #[test]
+    fn take_value_reward() {
+        let mut pool = RewardPool::new(0, 700, Perbill::from_percent(1));
+        assert_eq!(pool.take_value_reward(7, 2), 0);
+
+        pool.balance = 150;
+        assert_eq!(pool.take_value_reward(7, 2), 50);
+        assert_eq!(pool.balance, 50);
+        assert_eq!(pool.unclaimed, 100);
+
+        assert_eq!(pool.take_value_reward(7, 3), 16);
+        assert_eq!(pool.balance, 2);
+        assert_eq!(pool.unclaimed, 148);
+
+        assert_eq!(pool.take_value_reward(7, 0), 0);
+        assert_eq!(pool.take_value_reward(0, 2), 0);
+        assert_eq!(pool.balance, 2);
+
+        pool.claim(148);
+        pool.balance = 0;
+        assert!(pool.is_empty());
+    }
+}
@@ -0,0 +1,82 @@
+use codec::{Decode, Encode};
+use sp_arithmetic::Perbill;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// Count of last pushes, over which the mean deviation is rolled
+pub const STATS_WINDOW: u32 = 100;
+
+/// Statistics of source work in oracle
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct SourceStats {
+    /// Count of pushed values, that were used in calculation
+    pub pushes: u32,
+
+    /// Count of periods, in which the source didn't push any value
+    pub misses: u32,
+
+    /// Rolling mean of absolute deviation of pushed values from calculated ones,
+    /// relative to calculated values
+    pub mean_deviation: Perbill,
+}
+
+impl SourceStats {
+    /// Track period, in which the source didn't push
+    pub fn track_miss(&mut self) {
+        self.misses = self.misses.saturating_add(1);
+    }
+
+    /// Track deviation of value pushed by source from the calculated one
+    pub fn track_deviation(&mut self, deviation: Perbill) {
+        self.pushes = self.pushes.saturating_add(1);
+
+        let window = rstd::cmp::min(self.pushes, STATS_WINDOW) as u64;
+        let deviation = deviation.deconstruct() as u64;
+        let mean = self.mean_deviation.deconstruct() as u64;
+
+        self.mean_deviation =
+            Perbill::from_parts(((mean * (window - 1) + deviation) / window) as u32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_arithmetic::Perbill;
+
+    type SourceStats = super::SourceStats;
+
+    #[test]
+    fn track() {
+        let mut stats = SourceStats::default();
+
+        stats.track_deviation(Perbill::from_percent(10));
+        assert_eq!(stats.mean_deviation, Perbill::from_percent(10));
+
+        stats.track_deviation(Perbill::zero());
+        stats.track_miss();
+        stats.track_deviation(Perbill::from_percent(20));
+        assert_eq!(
+            stats,
+            SourceStats {
+                pushes: 3,
+                misses: 1,
+                mean_deviation: Perbill::from_percent(10),
+            }
+        );
+    }
+
+    #[test]
+    fn rolling_window() {
+        let mut stats = SourceStats::default();
+
+        (0..super::STATS_WINDOW).for_each(|_| stats.track_deviation(Perbill::zero()));
+        assert_eq!(stats.mean_deviation, Perbill::zero());
+
+        stats.track_deviation(Perbill::one());
+        assert_eq!(stats.pushes, super::STATS_WINDOW + 1);
+        assert_eq!(stats.mean_deviation, Perbill::from_percent(1));
+    }
+}
@@ -0,0 +1,150 @@
+use codec::{Decode, Encode};
+use rstd::prelude::Vec;
+use sp_arithmetic::traits::SimpleArithmetic;
+
+/// Max count of periods paid by consumer in one subscription
+pub const MAX_SUBSCRIPTION_PERIODS: u32 = 100;
+
+/// Subscription settings of permissioned oracle
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct SubscriptionConfig<AssetId, Balance> {
+    /// Asset in which fees are paid
+    pub asset_id: AssetId,
+
+    /// Fee paid by consumer for each period of subscription
+    pub period_fee: Balance,
+}
+
+/// Periods, that consumer paid for
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Subscription<Moment> {
+    /// The first paid period number
+    pub start: Moment,
+
+    /// Period number after the last paid period
+    pub end: Moment,
+}
+
+impl<Moment: SimpleArithmetic + Copy> Subscription<Moment> {
+    pub fn is_active(&self, period: Moment) -> bool {
+        self.start <= period && period < self.end
+    }
+
+    /// Subscription extended by `periods`, paid periods start not earlier than `period`
+    ///
+    /// Return subscription with the first newly paid period
+    pub fn extend(subscription: Option<Self>, period: Moment, periods: u32) -> (Self, Moment) {
+        let (start, first) = match subscription {
+            Some(subscription) if subscription.end > period => {
+                (subscription.start, subscription.end)
+            }
+            _ => (period, period),
+        };
+
+        let subscription = Subscription {
+            start,
+            end: first.saturating_add(Moment::from(periods)),
+        };
+        (subscription, first)
+    }
+}
+
+/// Fees paid by consumers for one period of oracle
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct PeriodFees<Balance> {
+    /// Fees paid for the period
+    pub total: Balance,
+
+    /// Fees not shared among sources yet
+    pub left: Balance,
+
+    /// Ids of values, whose fees were shared in the period
+    pub paid_values: Vec<u8>,
+}
+
+impl<Balance: SimpleArithmetic + Copy> PeriodFees<Balance> {
+    pub fn add(&mut self, fee: Balance) {
+        self.total = self.total.saturating_add(fee);
+        self.left = self.left.saturating_add(fee);
+    }
+
+    /// Take fees for value calculated in period
+    ///
+    /// Period fees are shared equally between `values_count` oracle values, and the value
+    /// fees are shared equally between `sources_count` sources, whose values were used.
+    /// Fees of each value are taken once per period.
+    ///
+    /// Return fee for each source
+    pub fn take_value_fee(
+        &mut self,
+        value_id: u8,
+        values_count: usize,
+        sources_count: usize,
+    ) -> Balance {
+        if values_count == 0 || sources_count == 0 || self.paid_values.contains(&value_id) {
+            return Balance::zero();
+        }
+        self.paid_values.push(value_id);
+
+        let value_fee = rstd::cmp::min(self.total / Balance::from(values_count as u32), self.left);
+        let source_fee = value_fee / Balance::from(sources_count as u32);
+
+        self.left -= source_fee * Balance::from(sources_count as u32);
+        source_fee
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    type Subscription = super::Subscription<u64>;
+    type PeriodFees = super::PeriodFees<u64>;
+
+    #[test]
+    fn extend() {
+        let (subscription, first) = Subscription::extend(None, 5, 3);
+        assert_eq!(subscription, Subscription { start: 5, end: 8 });
+        assert_eq!(first, 5);
+        assert!(!subscription.is_active(4));
+        assert!(subscription.is_active(5));
+        assert!(subscription.is_active(7));
+        assert!(!subscription.is_active(8));
+
+        // Active subscription continues from its end
+        let (subscription, first) = Subscription::extend(Some(subscription), 6, 2);
+        assert_eq!(subscription, Subscription { start: 5, end: 10 });
+        assert_eq!(first, 8);
+
+        // Expired subscription starts again from current period
+        let (subscription, first) = Subscription::extend(Some(subscription), 12, 1);
+        assert_eq!(subscription, Subscription { start: 12, end: 13 });
+        assert_eq!(first, 12);
+    }
+
+    #[test]
+    fn take_value_fee() {
+        let mut fees = PeriodFees::default();
+        assert_eq!(fees.take_value_fee(4, 2, 3), 0);
+
+        fees.add(100);
+        fees.add(50);
+        assert_eq!(fees.total, 150);
+        assert_eq!(fees.take_value_fee(0, 2, 3), 25);
+        assert_eq!(fees.left, 75);
+
+        // Fees of value are taken once per period
+        assert_eq!(fees.take_value_fee(0, 2, 3), 0);
+        assert_eq!(fees.left, 75);
+
+        assert_eq!(fees.take_value_fee(1, 2, 2), 37);
+        assert_eq!(fees.left, 1);
+        assert_eq!(fees.take_value_fee(2, 2, 2), 0);
+
+        assert_eq!(fees.take_value_fee(3, 0, 2), 0);
+        assert_eq!(fees.take_value_fee(3, 2, 0), 0);
+        assert_eq!(fees.left, 1);
+        assert_eq!(fees.paid_values, vec![4, 0, 1, 2]);
+    }
+}
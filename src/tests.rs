@@ -1,20 +1,42 @@
 // Tests to be written here
 
 use crate::mock::*;
+use crate::penalty::{Bond, SourcePenalty};
+use crate::stats::SourceStats;
+use crate::{
+    Aggregation, ClockSource, Dispersion, Formula, Part, PeriodAlignment, PeriodInfo, ServedValue,
+    SourceMode, Subscription, ValueInfo, ValueRef, MAX_BASKET_LEN, MAX_DERIVED_VALUES,
+    MAX_PAYLOAD_LEN,
+};
+use codec::{Decode, Encode};
 use frame_support::dispatch;
+use frame_support::storage::{unhashed, StorageMap, StorageValue};
 use frame_support::{assert_err, assert_ok};
+use sp_runtime::Perbill;
+use std::collections::BTreeMap;
 
 type Error = crate::Error<Test>;
 
 fn create_oracle(source_limit: u8) -> dispatch::DispatchResult {
+    create_oracle_with_quorum(source_limit, None)
+}
+
+fn create_oracle_with_quorum(
+    source_limit: u8,
+    quorum: Option<Perbill>,
+) -> dispatch::DispatchResult {
     OracleModule::create_oracle(
         Origin::signed(ALICE),
         to_raw(ORACLE_NAME),
         source_limit,
+        quorum,
         CALCULATION_PERIOD,
         AGGREGATION_PERIOD,
         ASSET_ID,
         get_asset_names(),
+        vec![0; get_asset_names().len()],
+        ClockSource::Timestamp,
+        PeriodAlignment::Creation,
     )
 }
 
@@ -210,3 +232,2323 @@ fn calculate() {
         }
     });
 }
+
+#[test]
+fn rewards() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+
+        let tolerance = Perbill::from_percent(1);
+        assert_err!(
+            OracleModule::set_reward_pool(Origin::signed(BOB), oracle_id, ASSET_ID, 700, tolerance),
+            Error::NotOracleOwner
+        );
+        assert_err!(
+            OracleModule::fund_reward_pool(Origin::signed(FRANK), oracle_id, 1000),
+            Error::NoRewardPool
+        );
+        assert_ok!(OracleModule::set_reward_pool(
+            Origin::signed(ALICE),
+            oracle_id,
+            ASSET_ID,
+            700,
+            tolerance
+        ));
+        assert_ok!(OracleModule::fund_reward_pool(
+            Origin::signed(FRANK),
+            oracle_id,
+            1000
+        ));
+        assert_eq!(
+            AssetsModule::free_balance(&ASSET_ID, &FRANK),
+            TOTAL_BALANCE - 1000
+        );
+
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        // EVE pushes values out of tolerance
+        [(CAROL, 0), (BOB, 0), (EVE, 100_000)]
+            .iter()
+            .for_each(|&(account, offset)| {
+                assert_ok!(OracleModule::push(
+                    Origin::signed(account),
+                    oracle_id,
+                    get_asset_value(0, offset)
+                ));
+            });
+
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+
+        // 700 per period for 7 values shared by two accurate sources
+        assert_eq!(OracleModule::rewards(oracle_id, CAROL), 50);
+        assert_eq!(OracleModule::rewards(oracle_id, BOB), 50);
+        assert_eq!(OracleModule::rewards(oracle_id, EVE), 0);
+        assert_eq!(OracleModule::reward_pool(oracle_id).unwrap().balance, 900);
+
+        let carol_balance = AssetsModule::free_balance(&ASSET_ID, &CAROL);
+        assert_ok!(OracleModule::claim_reward(Origin::signed(CAROL), oracle_id));
+        assert_eq!(
+            AssetsModule::free_balance(&ASSET_ID, &CAROL),
+            carol_balance + 50
+        );
+        assert_eq!(OracleModule::rewards(oracle_id, CAROL), 0);
+
+        assert_err!(
+            OracleModule::claim_reward(Origin::signed(CAROL), oracle_id),
+            Error::NothingToClaim
+        );
+        assert_err!(
+            OracleModule::claim_reward(Origin::signed(EVE), oracle_id),
+            Error::NothingToClaim
+        );
+
+        // Pool account of oracle holds funds left and rewards unclaimed by BOB
+        let pool = OracleModule::reward_pool(oracle_id).unwrap();
+        assert_eq!(pool.unclaimed, 50);
+        assert_eq!(
+            AssetsModule::free_balance(&ASSET_ID, &OracleModule::account_id(oracle_id)),
+            950
+        );
+        assert_ne!(
+            OracleModule::account_id(oracle_id),
+            OracleModule::account_id(oracle_id + 1)
+        );
+        assert_err!(
+            OracleModule::set_reward_pool(Origin::signed(ALICE), oracle_id, 1, 700, tolerance),
+            Error::RewardPoolNotEmpty
+        );
+    });
+}
+
+#[test]
+fn penalties() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+
+        assert_ok!(OracleModule::set_penalty_config(
+            Origin::signed(ALICE),
+            oracle_id,
+            Perbill::from_percent(1),
+            0,
+            1,
+            100
+        ));
+
+        assert_err!(
+            OracleModule::bond(Origin::signed(EVE), oracle_id, 500),
+            Error::NoBondAsset
+        );
+        assert_ok!(OracleModule::set_bond_asset(
+            Origin::signed(ALICE),
+            oracle_id,
+            ASSET_ID
+        ));
+        assert_err!(
+            OracleModule::set_bond_asset(Origin::signed(ALICE), oracle_id, ASSET_ID + 1),
+            Error::WrongBondAsset
+        );
+        assert_err!(
+            OracleModule::bond(Origin::signed(EVE), oracle_id + 1, 500),
+            Error::WrongOracleId
+        );
+
+        let eve_balance = AssetsModule::free_balance(&ASSET_ID, &EVE);
+        assert_ok!(OracleModule::bond(Origin::signed(EVE), oracle_id, 500));
+        assert_eq!(
+            AssetsModule::free_balance(&ASSET_ID, &EVE),
+            eve_balance - 500
+        );
+
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        // Sources of oracle with bond asset can't push without bond
+        assert_err!(
+            OracleModule::push(Origin::signed(CAROL), oracle_id, get_asset_value(0, 0)),
+            Error::NoBond
+        );
+        assert_ok!(OracleModule::bond(Origin::signed(CAROL), oracle_id, 500));
+        assert_ok!(OracleModule::bond(Origin::signed(BOB), oracle_id, 500));
+
+        // EVE pushes values out of tolerance
+        [(CAROL, 0), (BOB, 0), (EVE, 100_000)]
+            .iter()
+            .for_each(|&(account, offset)| {
+                assert_ok!(OracleModule::push(
+                    Origin::signed(account),
+                    oracle_id,
+                    get_asset_value(0, offset)
+                ));
+            });
+
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+
+        assert_eq!(
+            OracleModule::bonds(oracle_id, EVE),
+            Some(Bond {
+                asset_id: ASSET_ID,
+                amount: 400
+            })
+        );
+        assert_eq!(
+            OracleModule::penalties(oracle_id, EVE),
+            SourcePenalty::default()
+        );
+        assert_eq!(
+            OracleModule::penalties(oracle_id, BOB),
+            SourcePenalty::default()
+        );
+
+        assert_err!(
+            OracleModule::unbond(Origin::signed(EVE), oracle_id),
+            Error::SourceIsActive
+        );
+        assert_err!(
+            OracleModule::unbond(Origin::signed(FRANK), oracle_id),
+            Error::NoBond
+        );
+    });
+}
+
+#[test]
+fn source_statistics() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        [(CAROL, 0), (BOB, 0), (EVE, 100_000)]
+            .iter()
+            .for_each(|&(account, offset)| {
+                assert_ok!(OracleModule::push(
+                    Origin::signed(account),
+                    oracle_id,
+                    get_asset_value(0, offset)
+                ));
+            });
+
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 1));
+
+        let median = get_median_value(0, 0, vec![0, 0, 100_000]);
+        assert_eq!(
+            OracleModule::source_stats(oracle_id, CAROL),
+            SourceStats {
+                pushes: 2,
+                misses: 0,
+                mean_deviation: Perbill::zero(),
+            }
+        );
+        assert_eq!(
+            OracleModule::source_stats(oracle_id, EVE),
+            SourceStats {
+                pushes: 2,
+                misses: 0,
+                mean_deviation: Perbill::from_rational_approximation(100_000, median),
+            }
+        );
+        assert_eq!(
+            OracleModule::source_stats(oracle_id, ALICE),
+            SourceStats::default()
+        );
+    });
+}
+
+#[test]
+fn period_misses() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+        assert_ok!(OracleModule::set_penalty_config(
+            Origin::signed(ALICE),
+            oracle_id,
+            Perbill::from_percent(1),
+            2,
+            0,
+            100
+        ));
+        assert_ok!(OracleModule::set_bond_asset(
+            Origin::signed(ALICE),
+            oracle_id,
+            ASSET_ID
+        ));
+        [CAROL, BOB, EVE].iter().for_each(|&account| {
+            assert_ok!(OracleModule::bond(Origin::signed(account), oracle_id, 500));
+        });
+
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        let push = |accounts: &[AccountId]| {
+            accounts.iter().for_each(|account| {
+                assert_ok!(OracleModule::push(
+                    Origin::signed(*account),
+                    oracle_id,
+                    get_asset_value(0, 0)
+                ));
+            });
+        };
+
+        // EVE doesn't push, so values of period 0 can't be calculated
+        push(&[CAROL, BOB]);
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        assert_err!(
+            OracleModule::calculate(Origin::signed(ALICE), oracle_id + 1, 0),
+            Error::WrongOracleId
+        );
+        assert_err!(
+            OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0),
+            Error::NotEnoughValues
+        );
+        assert_err!(
+            OracleModule::calculate(Origin::signed(ALICE), oracle_id, 1),
+            Error::NotEnoughValues
+        );
+        assert_eq!(OracleModule::source_stats(oracle_id, EVE).misses, 0);
+
+        // Miss is tracked once per period, when its data is archived
+        TimestampModule::set_timestamp(CALCULATION_PERIOD + 1);
+        push(&[CAROL]);
+        assert_eq!(OracleModule::source_stats(oracle_id, EVE).misses, 1);
+        assert_eq!(OracleModule::penalties(oracle_id, EVE).misses, 1);
+        assert_eq!(OracleModule::source_stats(oracle_id, BOB).misses, 0);
+        assert_eq!(OracleModule::penalties(oracle_id, CAROL).misses, 0);
+
+        TimestampModule::set_timestamp(2 * CALCULATION_PERIOD + 1);
+        push(&[CAROL, EVE]);
+        assert_eq!(OracleModule::source_stats(oracle_id, EVE).misses, 2);
+        assert_eq!(OracleModule::source_stats(oracle_id, BOB).misses, 1);
+        assert_eq!(
+            OracleModule::penalties(oracle_id, EVE),
+            SourcePenalty::default()
+        );
+        assert_eq!(OracleModule::bonds(oracle_id, EVE).unwrap().amount, 400);
+    });
+}
+
+#[test]
+fn read_api() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_eq!(OracleModule::oracle_info(oracle_id), None);
+
+        assert_ok!(create_oracle(3));
+        assert_ok!(create_oracle(3));
+        assert_eq!(OracleModule::oracle_ids(), vec![oracle_id, oracle_id + 1]);
+
+        let info = OracleModule::oracle_info(oracle_id).unwrap();
+        assert_eq!(info.owner, Some(ALICE));
+        assert_eq!(info.period, CALCULATION_PERIOD);
+        assert_eq!(info.aggregate_part, AGGREGATION_PERIOD);
+        assert_eq!(info.clock, ClockSource::Timestamp);
+        assert_eq!(info.quorum, None);
+        assert_eq!(info.values_names, get_asset_names());
+
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+        [CAROL, BOB, EVE].iter().for_each(|&account| {
+            assert_ok!(OracleModule::push(
+                Origin::signed(account),
+                oracle_id,
+                get_asset_value(0, 0)
+            ));
+        });
+
+        let mut sources = OracleModule::sources(oracle_id);
+        sources.sort();
+        assert_eq!(sources, vec![BOB, CAROL, EVE]);
+        assert_eq!(
+            OracleModule::period_info(oracle_id),
+            Some(PeriodInfo {
+                number: 0,
+                part: Part::Aggregate
+            })
+        );
+        assert!(OracleModule::values_info(oracle_id)
+            .iter()
+            .all(|info| !info.is_calculate_allowed && info.value.is_none()));
+
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        assert!(OracleModule::values_info(oracle_id)
+            .iter()
+            .all(|info| info.is_calculate_allowed));
+
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 2));
+        assert_eq!(
+            OracleModule::value_info_by_name(oracle_id, to_raw(EUR_USD)),
+            Some(ValueInfo {
+                name: to_raw(EUR_USD),
+                value: Some(EUR_USD_DATA[0]),
+                payload: None,
+                decimals: 0,
+                aggregation: Aggregation::Median,
+                last_changed: Some(AGGREGATION_PERIOD + 1),
+                observed: Some(0),
+                dispersion: Some(Dispersion {
+                    sources: 3,
+                    min: EUR_USD_DATA[0],
+                    max: EUR_USD_DATA[0],
+                    iqr: 0,
+                }),
+                is_calculate_allowed: false,
+                is_degraded: false,
+                is_paused: false,
+                is_frozen: false,
+                frozen_value: None,
+            })
+        );
+        assert_eq!(
+            OracleModule::value_info_by_name(oracle_id, to_raw("EUR/JPY")),
+            None
+        );
+        assert_eq!(OracleModule::value_info(oracle_id, 100), None);
+    });
+}
+
+#[test]
+fn deviation_update() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+
+        let threshold = Some(Perbill::from_percent(1));
+        assert_err!(
+            OracleModule::set_deviation_threshold(Origin::signed(BOB), oracle_id, threshold),
+            Error::NotOracleOwner
+        );
+        assert_ok!(OracleModule::set_deviation_threshold(
+            Origin::signed(ALICE),
+            oracle_id,
+            threshold
+        ));
+
+        // 700 per period for 7 values shared by three sources
+        assert_ok!(OracleModule::set_reward_pool(
+            Origin::signed(ALICE),
+            oracle_id,
+            ASSET_ID,
+            700,
+            Perbill::from_percent(1)
+        ));
+        assert_ok!(OracleModule::fund_reward_pool(
+            Origin::signed(FRANK),
+            oracle_id,
+            1000
+        ));
+
+        let accounts = [CAROL, BOB, EVE];
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        let push_all = |offset| {
+            accounts.iter().for_each(|&account| {
+                assert_ok!(OracleModule::push(
+                    Origin::signed(account),
+                    oracle_id,
+                    get_asset_value(0, offset)
+                ));
+            });
+        };
+
+        push_all(0);
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+
+        // Values within threshold wait for calculate part
+        TimestampModule::set_timestamp(CALCULATION_PERIOD);
+        push_all(10);
+        assert_err!(
+            OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0),
+            Error::NotCalculateTime
+        );
+
+        push_all(100_000);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+        assert_eq!(
+            OracleModule::oracles(oracle_id).values[0].value,
+            Some(BTC_USD_DATA[0] + 100_000)
+        );
+        assert_eq!(OracleModule::rewards(oracle_id, CAROL), 2 * 33);
+
+        // Sources are rewarded and tracked once per period for value updated by deviation
+        TimestampModule::set_timestamp(CALCULATION_PERIOD + AGGREGATION_PERIOD + 1);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+        assert_eq!(OracleModule::rewards(oracle_id, CAROL), 2 * 33);
+        assert_eq!(
+            OracleModule::reward_pool(oracle_id).unwrap().balance,
+            1000 - 2 * 99
+        );
+        assert_eq!(OracleModule::source_stats(oracle_id, CAROL).pushes, 2);
+        assert_eq!(OracleModule::rewarded_period(oracle_id, 0), Some(1));
+    });
+}
+
+#[test]
+fn heartbeat() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+
+        assert_err!(
+            OracleModule::set_heartbeat(Origin::signed(ALICE), oracle_id, 100, Some(1)),
+            Error::WrongValueId
+        );
+        assert_ok!(OracleModule::set_heartbeat(
+            Origin::signed(ALICE),
+            oracle_id,
+            0,
+            Some(1)
+        ));
+
+        let accounts = [CAROL, BOB, EVE];
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        let push_all = || {
+            accounts.iter().for_each(|&account| {
+                assert_ok!(OracleModule::push(
+                    Origin::signed(account),
+                    oracle_id,
+                    get_asset_value(0, 0)
+                ));
+            });
+        };
+        let is_degraded = || {
+            OracleModule::value_info(oracle_id, 0)
+                .map(|info| info.is_degraded)
+                .unwrap()
+        };
+
+        push_all();
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+        assert!(!is_degraded());
+
+        // Nobody calculated the value in period 1
+        TimestampModule::set_timestamp(2 * CALCULATION_PERIOD);
+        assert!(is_degraded());
+        assert_ok!(OracleModule::check_heartbeats(
+            Origin::signed(FRANK),
+            oracle_id
+        ));
+        assert!(is_degraded());
+        assert_err!(
+            OracleModule::check_heartbeats(Origin::signed(FRANK), oracle_id + 1),
+            Error::WrongOracleId
+        );
+
+        push_all();
+        TimestampModule::set_timestamp(2 * CALCULATION_PERIOD + AGGREGATION_PERIOD + 1);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+        assert!(!is_degraded());
+        assert!(!OracleModule::value_info(oracle_id, 1).unwrap().is_degraded);
+    });
+}
+
+#[test]
+fn block_number_clock() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+
+        SystemModule::set_block_number(100);
+        TimestampModule::set_timestamp(100_000);
+        assert_ok!(OracleModule::create_oracle(
+            Origin::signed(ALICE),
+            to_raw(ORACLE_NAME),
+            3,
+            None,
+            10,
+            5,
+            ASSET_ID,
+            get_asset_names(),
+            vec![0; get_asset_names().len()],
+            ClockSource::BlockNumber,
+            PeriodAlignment::Creation,
+        ));
+
+        let accounts = [CAROL, BOB, EVE];
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        accounts.iter().for_each(|&account| {
+            assert_ok!(OracleModule::push(
+                Origin::signed(account),
+                oracle_id,
+                get_asset_value(0, 0)
+            ));
+        });
+
+        // Timestamp doesn't affect periods of oracle
+        TimestampModule::set_timestamp(100_000 + CALCULATION_PERIOD);
+        assert_err!(
+            OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0),
+            Error::NotCalculateTime
+        );
+
+        SystemModule::set_block_number(106);
+        assert_eq!(
+            OracleModule::period_info(oracle_id),
+            Some(PeriodInfo {
+                number: 0,
+                part: Part::Calculate
+            })
+        );
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+        assert_eq!(
+            OracleModule::oracles(oracle_id).values[0].last_changed,
+            Some(106)
+        );
+    });
+}
+
+#[test]
+fn aligned_periods() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+
+        TimestampModule::set_timestamp(12_345);
+        assert_ok!(OracleModule::create_oracle(
+            Origin::signed(ALICE),
+            to_raw(ORACLE_NAME),
+            3,
+            None,
+            1000,
+            500,
+            ASSET_ID,
+            get_asset_names(),
+            vec![0; get_asset_names().len()],
+            ClockSource::Timestamp,
+            PeriodAlignment::Period,
+        ));
+
+        let period_at = |now| {
+            TimestampModule::set_timestamp(now);
+            OracleModule::period_info(oracle_id)
+        };
+
+        assert_eq!(
+            period_at(12_500),
+            Some(PeriodInfo {
+                number: 0,
+                part: Part::Aggregate
+            })
+        );
+        assert_eq!(
+            period_at(12_501),
+            Some(PeriodInfo {
+                number: 0,
+                part: Part::Calculate
+            })
+        );
+        assert_eq!(
+            period_at(13_000),
+            Some(PeriodInfo {
+                number: 1,
+                part: Part::Aggregate
+            })
+        );
+    });
+}
+
+#[test]
+fn period_change() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+
+        let accounts = [CAROL, BOB, EVE];
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        let push_all = || {
+            accounts.iter().for_each(|&account| {
+                assert_ok!(OracleModule::push(
+                    Origin::signed(account),
+                    oracle_id,
+                    get_asset_value(0, 0)
+                ));
+            });
+        };
+
+        push_all();
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+
+        assert_err!(
+            OracleModule::schedule_period_change(Origin::signed(BOB), oracle_id, 100, 50),
+            Error::NotOracleOwner
+        );
+        assert_err!(
+            OracleModule::schedule_period_change(Origin::signed(ALICE), oracle_id, 50, 50),
+            Error::WrongPeriods
+        );
+        assert_ok!(OracleModule::schedule_period_change(
+            Origin::signed(ALICE),
+            oracle_id,
+            100,
+            50
+        ));
+        assert_eq!(
+            OracleModule::oracle_info(oracle_id).unwrap().period,
+            CALCULATION_PERIOD
+        );
+
+        // Aggregate part of the first changed period
+        TimestampModule::set_timestamp(CALCULATION_PERIOD + 20);
+        assert_eq!(
+            OracleModule::period_info(oracle_id),
+            Some(PeriodInfo {
+                number: 1,
+                part: Part::Aggregate
+            })
+        );
+        push_all();
+        assert_err!(
+            OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0),
+            Error::NotCalculateTime
+        );
+
+        // Value, that was not calculated before the change, is calculated with data of period 0
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 1));
+        assert_eq!(
+            OracleModule::oracles(oracle_id).values[1].get(),
+            Some((get_asset_value(0, 0)[1], CALCULATION_PERIOD + 20))
+        );
+
+        TimestampModule::set_timestamp(CALCULATION_PERIOD + 51);
+        assert_eq!(
+            OracleModule::period_info(oracle_id),
+            Some(PeriodInfo {
+                number: 1,
+                part: Part::Calculate
+            })
+        );
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+
+        TimestampModule::set_timestamp(CALCULATION_PERIOD + 100);
+        assert_eq!(
+            OracleModule::period_info(oracle_id),
+            Some(PeriodInfo {
+                number: 2,
+                part: Part::Aggregate
+            })
+        );
+        let info = OracleModule::oracle_info(oracle_id).unwrap();
+        assert_eq!((info.period, info.aggregate_part), (100, 50));
+    });
+}
+
+#[test]
+fn pause_resume() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+
+        let accounts = [CAROL, BOB, EVE];
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        let push =
+            |account| OracleModule::push(Origin::signed(account), oracle_id, get_asset_value(0, 0));
+
+        accounts
+            .iter()
+            .for_each(|&account| assert_ok!(push(account)));
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+
+        assert_err!(
+            OracleModule::pause_oracle(Origin::signed(BOB), oracle_id),
+            Error::NotOracleOwner
+        );
+        assert_err!(
+            OracleModule::pause_oracle(Origin::ROOT, oracle_id + 1),
+            Error::WrongOracleId
+        );
+        assert_err!(
+            OracleModule::resume_oracle(Origin::signed(ALICE), oracle_id),
+            Error::OracleNotPaused
+        );
+        assert_ok!(OracleModule::pause_oracle(Origin::signed(ALICE), oracle_id));
+        assert_err!(
+            OracleModule::pause_oracle(Origin::ROOT, oracle_id),
+            Error::OraclePaused
+        );
+
+        TimestampModule::set_timestamp(5 * CALCULATION_PERIOD);
+        assert_err!(push(CAROL), Error::OraclePaused);
+        assert_err!(
+            OracleModule::calculate(Origin::signed(ALICE), oracle_id, 1),
+            Error::OraclePaused
+        );
+
+        let info = OracleModule::value_info(oracle_id, 0).unwrap();
+        assert_eq!(info.value, Some(get_asset_value(0, 0)[0]));
+        assert!(info.is_paused);
+        assert!(!info.is_calculate_allowed);
+        assert!(OracleModule::oracle_info(oracle_id).unwrap().is_paused);
+
+        let resumed_at = 5 * CALCULATION_PERIOD + 7;
+        TimestampModule::set_timestamp(resumed_at);
+        assert_ok!(OracleModule::resume_oracle(Origin::ROOT, oracle_id));
+        assert!(!OracleModule::value_info(oracle_id, 0).unwrap().is_paused);
+        assert_eq!(
+            OracleModule::period_info(oracle_id),
+            Some(PeriodInfo {
+                number: 6,
+                part: Part::Aggregate
+            })
+        );
+
+        accounts
+            .iter()
+            .for_each(|&account| assert_ok!(push(account)));
+        TimestampModule::set_timestamp(resumed_at + AGGREGATION_PERIOD + 1);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 1));
+        assert_eq!(
+            OracleModule::oracles(oracle_id).values[1].last_changed,
+            Some(resumed_at + AGGREGATION_PERIOD + 1)
+        );
+    });
+}
+
+#[test]
+fn halt() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        assert!(OracleModule::set_halted(Origin::signed(ALICE), true).is_err());
+        assert_ok!(OracleModule::set_halted(Origin::ROOT, true));
+        assert!(OracleModule::is_halted());
+
+        assert_err!(
+            OracleModule::push(Origin::signed(CAROL), oracle_id, get_asset_value(0, 0)),
+            Error::PalletHalted
+        );
+        assert_err!(
+            OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0),
+            Error::PalletHalted
+        );
+
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        assert!(
+            !OracleModule::value_info(oracle_id, 0)
+                .unwrap()
+                .is_calculate_allowed
+        );
+
+        assert_ok!(OracleModule::set_halted(Origin::ROOT, false));
+        assert!(
+            OracleModule::value_info(oracle_id, 0)
+                .unwrap()
+                .is_calculate_allowed
+        );
+
+        TimestampModule::set_timestamp(CALCULATION_PERIOD);
+        assert_ok!(OracleModule::push(
+            Origin::signed(CAROL),
+            oracle_id,
+            get_asset_value(0, 0)
+        ));
+    });
+}
+
+#[test]
+fn circuit_breaker() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+
+        let accounts = [CAROL, BOB, EVE];
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        assert_err!(
+            OracleModule::set_circuit_breaker(
+                Origin::signed(BOB),
+                oracle_id,
+                0,
+                Some(Perbill::from_percent(10))
+            ),
+            Error::NotOracleOwner
+        );
+        assert_ok!(OracleModule::set_circuit_breaker(
+            Origin::signed(ALICE),
+            oracle_id,
+            0,
+            Some(Perbill::from_percent(10))
+        ));
+        assert_err!(
+            OracleModule::confirm_value(Origin::signed(ALICE), oracle_id, 0),
+            Error::ValueNotFrozen
+        );
+
+        let push_and_calculate = |period: u128, value: Balance| {
+            let begin = period * CALCULATION_PERIOD;
+            TimestampModule::set_timestamp(begin);
+            accounts.iter().for_each(|&account| {
+                assert_ok!(OracleModule::push(
+                    Origin::signed(account),
+                    oracle_id,
+                    vec![value; get_asset_names().len()]
+                ));
+            });
+            TimestampModule::set_timestamp(begin + AGGREGATION_PERIOD + 1);
+            assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+        };
+
+        push_and_calculate(0, 1000);
+        assert_eq!(
+            OracleModule::get_or_calculate_external_value(Origin::signed(ALICE), oracle_id, 0),
+            Ok((1000, AGGREGATION_PERIOD + 1))
+        );
+
+        // The previous value stays readable, while the new one is frozen
+        UpdateRecorder::take();
+        push_and_calculate(1, 2000);
+        assert_eq!(UpdateRecorder::take(), vec![]);
+        assert_eq!(
+            OracleModule::get_or_calculate_external_value(Origin::signed(ALICE), oracle_id, 0),
+            Ok((1000, AGGREGATION_PERIOD + 1))
+        );
+        assert_err!(
+            OracleModule::get_served_value(&JUDY, oracle_id, 0),
+            Error::ValueFrozen
+        );
+        let info = OracleModule::value_info(oracle_id, 0).unwrap();
+        assert_eq!(info.value, Some(1000));
+        assert_eq!(info.frozen_value, Some(2000));
+        assert!(info.is_frozen);
+
+        assert_err!(
+            OracleModule::confirm_value(Origin::signed(BOB), oracle_id, 0),
+            Error::NotOracleOwner
+        );
+        assert_ok!(OracleModule::confirm_value(
+            Origin::signed(ALICE),
+            oracle_id,
+            0
+        ));
+        assert_eq!(
+            OracleModule::get_or_calculate_external_value(Origin::signed(ALICE), oracle_id, 0),
+            Ok((2000, CALCULATION_PERIOD + AGGREGATION_PERIOD + 1))
+        );
+        assert_eq!(
+            UpdateRecorder::take(),
+            vec![(
+                oracle_id,
+                0,
+                2000,
+                CALCULATION_PERIOD + AGGREGATION_PERIOD + 1
+            )]
+        );
+        assert_eq!(
+            OracleModule::value_info(oracle_id, 0).unwrap().frozen_value,
+            None
+        );
+
+        // Change of threshold keeps the frozen value, removal of breaker drops it
+        push_and_calculate(2, 3000);
+        assert_ok!(OracleModule::set_circuit_breaker(
+            Origin::signed(ALICE),
+            oracle_id,
+            0,
+            Some(Perbill::from_percent(20))
+        ));
+        assert_eq!(
+            OracleModule::value_info(oracle_id, 0).unwrap().frozen_value,
+            Some(3000)
+        );
+        assert_ok!(OracleModule::set_circuit_breaker(
+            Origin::signed(ALICE),
+            oracle_id,
+            0,
+            None
+        ));
+        let info = OracleModule::value_info(oracle_id, 0).unwrap();
+        assert_eq!(info.value, Some(2000));
+        assert!(!info.is_frozen);
+    });
+}
+
+#[test]
+fn calculate_past() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+
+        let accounts = [CAROL, BOB, EVE];
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        assert_err!(
+            OracleModule::set_history_depth(Origin::signed(ALICE), oracle_id, 0),
+            Error::WrongHistoryDepth
+        );
+        assert_ok!(OracleModule::set_history_depth(
+            Origin::signed(ALICE),
+            oracle_id,
+            3
+        ));
+
+        // Values pushed, but not calculated in periods 0..3
+        (0..3).for_each(|period| {
+            TimestampModule::set_timestamp(period * CALCULATION_PERIOD);
+            accounts.iter().for_each(|&account| {
+                assert_ok!(OracleModule::push(
+                    Origin::signed(account),
+                    oracle_id,
+                    vec![100 + period; get_asset_names().len()]
+                ));
+            });
+        });
+
+        TimestampModule::set_timestamp(5 * CALCULATION_PERIOD);
+        assert_err!(
+            OracleModule::calculate_past(Origin::signed(ALICE), oracle_id, 0, 5),
+            Error::NotCalculateTime
+        );
+        assert_err!(
+            OracleModule::calculate_past(Origin::signed(ALICE), oracle_id, 0, 3),
+            Error::NoPeriodData
+        );
+        assert_err!(
+            OracleModule::calculate_past(Origin::signed(ALICE), oracle_id + 1, 0, 0),
+            Error::WrongOracleId
+        );
+
+        (0..3).for_each(|period| {
+            assert_ok!(OracleModule::calculate_past(
+                Origin::signed(FRANK),
+                oracle_id,
+                0,
+                period
+            ));
+            assert_eq!(
+                OracleModule::past_values(oracle_id, (0, period)),
+                Some(100 + period)
+            );
+        });
+        assert_eq!(OracleModule::past_values(oracle_id, (1, 0)), None);
+
+        assert_err!(
+            OracleModule::calculate_past(Origin::signed(FRANK), oracle_id, 0, 1),
+            Error::NotEnoughValues
+        );
+
+        // History is pruned to the last periods by depth
+        assert_eq!(OracleModule::history_periods(oracle_id), vec![0, 1, 2]);
+        assert_ok!(OracleModule::set_history_depth(
+            Origin::signed(ALICE),
+            oracle_id,
+            1
+        ));
+        assert_eq!(OracleModule::history_periods(oracle_id), vec![2]);
+        assert_eq!(OracleModule::history(oracle_id, 0), None);
+        assert_err!(
+            OracleModule::calculate_past(Origin::signed(FRANK), oracle_id, 1, 0),
+            Error::NoPeriodData
+        );
+        assert_ok!(OracleModule::calculate_past(
+            Origin::signed(FRANK),
+            oracle_id,
+            1,
+            2
+        ));
+    });
+}
+
+#[test]
+fn few_sources() {
+    new_test_ext().execute_with(|| {
+        assert_err!(create_oracle(0), Error::WrongSourceLimit);
+
+        let single_id = OracleModule::next_oracle_id();
+        let single_table = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(1));
+
+        let pair_id = OracleModule::next_oracle_id();
+        let pair_table = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(2));
+
+        self_votes(single_table, vec![(CAROL, 99)]);
+        self_votes(pair_table, vec![(CAROL, 99), (BOB, 100)]);
+
+        assert_ok!(OracleModule::push(
+            Origin::signed(CAROL),
+            single_id,
+            get_asset_value(0, 0)
+        ));
+        assert_ok!(OracleModule::push(
+            Origin::signed(CAROL),
+            pair_id,
+            get_asset_value(0, 0)
+        ));
+        assert_ok!(OracleModule::push(
+            Origin::signed(BOB),
+            pair_id,
+            get_asset_value(0, 100)
+        ));
+
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), single_id, 0));
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), pair_id, 0));
+
+        assert_eq!(
+            OracleModule::oracles(single_id).values[0].value,
+            Some(get_median_value(0, 0, vec![0]))
+        );
+        assert_eq!(
+            OracleModule::oracles(pair_id).values[0].value,
+            Some(get_median_value(0, 0, vec![0, 100]))
+        );
+    });
+}
+
+#[test]
+fn quorum() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        assert_err!(
+            OracleModule::set_quorum(Origin::signed(BOB), oracle_id, None),
+            Error::NotOracleOwner
+        );
+        assert_err!(
+            OracleModule::set_quorum(Origin::signed(ALICE), oracle_id, Some(Perbill::zero())),
+            Error::WrongQuorum
+        );
+
+        // Zero quorum is rejected before tablescore table is created
+        assert_err!(
+            create_oracle_with_quorum(3, Some(Perbill::zero())),
+            Error::WrongQuorum
+        );
+        assert_eq!(TablescoreModule::next_table_id(), table_id + 1);
+        assert_ok!(OracleModule::set_quorum(
+            Origin::signed(ALICE),
+            oracle_id,
+            Some(Perbill::from_percent(60))
+        ));
+        assert_eq!(
+            OracleModule::oracle_info(oracle_id).unwrap().quorum,
+            Some(Perbill::from_percent(60))
+        );
+
+        assert_ok!(OracleModule::push(
+            Origin::signed(CAROL),
+            oracle_id,
+            get_asset_value(0, 0)
+        ));
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        assert_err!(
+            OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0),
+            Error::NotEnoughValues
+        );
+
+        TimestampModule::set_timestamp(CALCULATION_PERIOD);
+        [CAROL, BOB].iter().for_each(|&account| {
+            assert_ok!(OracleModule::push(
+                Origin::signed(account),
+                oracle_id,
+                get_asset_value(0, 0)
+            ));
+        });
+        TimestampModule::set_timestamp(CALCULATION_PERIOD + AGGREGATION_PERIOD + 1);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+        assert_eq!(
+            OracleModule::oracles(oracle_id).values[0].value,
+            Some(get_asset_value(0, 0)[0])
+        );
+    });
+}
+
+#[test]
+fn decimals() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let create = |decimals| {
+            OracleModule::create_oracle(
+                Origin::signed(ALICE),
+                to_raw(ORACLE_NAME),
+                3,
+                None,
+                CALCULATION_PERIOD,
+                AGGREGATION_PERIOD,
+                ASSET_ID,
+                get_asset_names(),
+                decimals,
+                ClockSource::Timestamp,
+                PeriodAlignment::Creation,
+            )
+        };
+
+        assert_err!(create(vec![8, 2]), Error::WrongValuesCount);
+        assert_ok!(create((0..get_asset_names().len() as u8).collect()));
+
+        let decimals: Vec<u8> = OracleModule::values_info(oracle_id)
+            .into_iter()
+            .map(|info| info.decimals)
+            .collect();
+        assert_eq!(
+            decimals,
+            (0..get_asset_names().len() as u8).collect::<Vec<_>>()
+        );
+    });
+}
+
+#[test]
+fn majority() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        let majority = Aggregation::Majority(Perbill::from_percent(50));
+        assert_err!(
+            OracleModule::set_aggregation(Origin::signed(BOB), oracle_id, 0, majority),
+            Error::NotOracleOwner
+        );
+        assert_err!(
+            OracleModule::set_aggregation(Origin::signed(ALICE), oracle_id, 100, majority),
+            Error::WrongValueId
+        );
+        assert_ok!(OracleModule::set_aggregation(
+            Origin::signed(ALICE),
+            oracle_id,
+            0,
+            majority
+        ));
+        assert_eq!(
+            OracleModule::value_info(oracle_id, 0).unwrap().aggregation,
+            majority
+        );
+
+        assert_ok!(OracleModule::set_bond_asset(
+            Origin::signed(ALICE),
+            oracle_id,
+            ASSET_ID
+        ));
+        [(CAROL, 100), (BOB, 100), (EVE, 500)]
+            .iter()
+            .for_each(|&(account, amount)| {
+                assert_ok!(OracleModule::bond(
+                    Origin::signed(account),
+                    oracle_id,
+                    amount
+                ));
+            });
+
+        let push_votes = || {
+            [(CAROL, 1), (BOB, 1), (EVE, 0)]
+                .iter()
+                .for_each(|&(account, vote)| {
+                    assert_ok!(OracleModule::push(
+                        Origin::signed(account),
+                        oracle_id,
+                        vec![vote; get_asset_names().len()]
+                    ));
+                });
+        };
+
+        // EVE bond outweighs bonds of the others
+        push_votes();
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+        assert_eq!(OracleModule::oracles(oracle_id).values[0].value, Some(0));
+        assert_eq!(
+            OracleModule::source_stats(oracle_id, BOB).mean_deviation,
+            Perbill::one()
+        );
+        assert_eq!(
+            OracleModule::source_stats(oracle_id, EVE).mean_deviation,
+            Perbill::zero()
+        );
+
+        // Equal bonds of both sides
+        assert_ok!(OracleModule::bond(Origin::signed(CAROL), oracle_id, 300));
+        TimestampModule::set_timestamp(CALCULATION_PERIOD);
+        push_votes();
+        TimestampModule::set_timestamp(CALCULATION_PERIOD + AGGREGATION_PERIOD + 1);
+        assert_err!(
+            OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0),
+            Error::NoMajority
+        );
+    });
+}
+
+#[test]
+fn payload() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        assert_ok!(OracleModule::set_aggregation(
+            Origin::signed(ALICE),
+            oracle_id,
+            1,
+            Aggregation::Hash(Perbill::from_percent(60))
+        ));
+
+        assert_err!(
+            OracleModule::push_payload(Origin::signed(CAROL), oracle_id, 0, b"root".to_vec()),
+            Error::NotPayloadValue
+        );
+        assert_err!(
+            OracleModule::push_payload(Origin::signed(FRANK), oracle_id, 1, b"root".to_vec()),
+            Error::AccountPermissionDenied
+        );
+        assert_err!(
+            OracleModule::push_payload(
+                Origin::signed(CAROL),
+                oracle_id,
+                1,
+                vec![0; MAX_PAYLOAD_LEN + 1]
+            ),
+            Error::WrongPayloadLength
+        );
+
+        [(CAROL, "root"), (BOB, "fake")]
+            .iter()
+            .for_each(|&(account, payload)| {
+                assert_ok!(OracleModule::push_payload(
+                    Origin::signed(account),
+                    oracle_id,
+                    1,
+                    to_raw(payload)
+                ));
+            });
+        assert_eq!(
+            OracleModule::value_info(oracle_id, 1).unwrap().payload,
+            None
+        );
+
+        TimestampModule::set_timestamp(10);
+        assert_ok!(OracleModule::push_payload(
+            Origin::signed(EVE),
+            oracle_id,
+            1,
+            to_raw("root")
+        ));
+
+        let info = OracleModule::value_info(oracle_id, 1).unwrap();
+        assert_eq!(info.payload, Some(to_raw("root")));
+        assert_eq!(info.value, None);
+        assert_eq!(info.last_changed, Some(10));
+        assert!(!info.is_calculate_allowed);
+
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        assert_err!(
+            OracleModule::calculate(Origin::signed(ALICE), oracle_id, 1),
+            Error::NotCalculateTime
+        );
+        assert_err!(
+            OracleModule::push_payload(Origin::signed(CAROL), oracle_id, 1, to_raw("root")),
+            Error::NotAggregationTime
+        );
+
+        // Source, which pushed only payload, doesn't miss the period
+        TimestampModule::set_timestamp(CALCULATION_PERIOD);
+        assert_ok!(OracleModule::push(
+            Origin::signed(CAROL),
+            oracle_id,
+            get_asset_value(0, 0)
+        ));
+        assert_ok!(OracleModule::push_payload(
+            Origin::signed(BOB),
+            oracle_id,
+            1,
+            to_raw("root")
+        ));
+
+        TimestampModule::set_timestamp(2 * CALCULATION_PERIOD);
+        assert_ok!(OracleModule::push_payload(
+            Origin::signed(BOB),
+            oracle_id,
+            1,
+            to_raw("root")
+        ));
+        assert_eq!(OracleModule::source_stats(oracle_id, CAROL).misses, 0);
+        assert_eq!(OracleModule::source_stats(oracle_id, BOB).misses, 0);
+        assert_eq!(OracleModule::source_stats(oracle_id, EVE).misses, 1);
+    });
+}
+
+#[test]
+fn observation() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        assert_err!(
+            OracleModule::push(Origin::signed(CAROL), oracle_id + 1, get_asset_value(0, 0)),
+            Error::WrongOracleId
+        );
+        assert_err!(
+            OracleModule::push_observed(
+                Origin::signed(CAROL),
+                oracle_id + 1,
+                get_asset_value(0, 0),
+                0
+            ),
+            Error::WrongOracleId
+        );
+
+        TimestampModule::set_timestamp(100);
+        assert_err!(
+            OracleModule::push_observed(
+                Origin::signed(CAROL),
+                oracle_id,
+                get_asset_value(0, 0),
+                60
+            ),
+            Error::WrongObservationTime
+        );
+
+        assert_err!(
+            OracleModule::set_observation_tolerance(Origin::signed(BOB), oracle_id, 50),
+            Error::NotOracleOwner
+        );
+        assert_ok!(OracleModule::set_observation_tolerance(
+            Origin::signed(ALICE),
+            oracle_id,
+            50
+        ));
+        assert_err!(
+            OracleModule::push_observed(Origin::signed(EVE), oracle_id, get_asset_value(0, 0), 40),
+            Error::WrongObservationTime
+        );
+
+        [(CAROL, 60), (BOB, 90)]
+            .iter()
+            .for_each(|&(account, observed)| {
+                assert_ok!(OracleModule::push_observed(
+                    Origin::signed(account),
+                    oracle_id,
+                    get_asset_value(0, 0),
+                    observed
+                ));
+            });
+        assert_ok!(OracleModule::push(
+            Origin::signed(EVE),
+            oracle_id,
+            get_asset_value(0, 0)
+        ));
+
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+
+        let info = OracleModule::value_info(oracle_id, 0).unwrap();
+        assert_eq!(info.last_changed, Some(AGGREGATION_PERIOD + 1));
+        assert_eq!(info.observed, Some(90));
+    });
+}
+
+#[test]
+fn derived_values() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(OracleModule::create_oracle(
+            Origin::signed(ALICE),
+            to_raw(ORACLE_NAME),
+            3,
+            None,
+            CALCULATION_PERIOD,
+            AGGREGATION_PERIOD,
+            ASSET_ID,
+            get_asset_names(),
+            vec![0, 0, 4, 4, 0, 0, 2],
+            ClockSource::Timestamp,
+            PeriodAlignment::Creation,
+        ));
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        let value = |value_id| ValueRef {
+            oracle_id,
+            value_id,
+        };
+        let eur_jpy = Formula::Multiply(value(2), value(6));
+
+        assert_err!(
+            OracleModule::add_derived_value(
+                Origin::signed(BOB),
+                oracle_id,
+                to_raw("EUR/JPY"),
+                2,
+                eur_jpy.clone()
+            ),
+            Error::NotOracleOwner
+        );
+        assert_err!(
+            OracleModule::add_derived_value(
+                Origin::signed(ALICE),
+                oracle_id,
+                to_raw("EUR/JPY"),
+                2,
+                Formula::Invert(value(100))
+            ),
+            Error::WrongValueId
+        );
+        assert_err!(
+            OracleModule::add_derived_value(
+                Origin::signed(ALICE),
+                oracle_id,
+                to_raw("EUR/JPY"),
+                2,
+                Formula::Invert(ValueRef {
+                    oracle_id: oracle_id + 1,
+                    value_id: 0
+                })
+            ),
+            Error::WrongOracleId
+        );
+        assert_err!(
+            OracleModule::add_derived_value(
+                Origin::signed(ALICE),
+                oracle_id,
+                to_raw("Basket"),
+                2,
+                Formula::Basket(vec![])
+            ),
+            Error::WrongFormula
+        );
+        assert_err!(
+            OracleModule::add_derived_value(
+                Origin::signed(ALICE),
+                oracle_id,
+                to_raw("Basket"),
+                2,
+                Formula::Basket(vec![(value(0), Perbill::zero()); MAX_BASKET_LEN + 1])
+            ),
+            Error::WrongFormula
+        );
+
+        // Inputs of derived value must use clock of its oracle
+        let block_oracle_id = OracleModule::next_oracle_id();
+        assert_ok!(OracleModule::create_oracle(
+            Origin::signed(ALICE),
+            to_raw(ORACLE_NAME),
+            3,
+            None,
+            10,
+            5,
+            ASSET_ID,
+            get_asset_names(),
+            vec![0; get_asset_names().len()],
+            ClockSource::BlockNumber,
+            PeriodAlignment::Creation,
+        ));
+        assert_err!(
+            OracleModule::add_derived_value(
+                Origin::signed(ALICE),
+                oracle_id,
+                to_raw("1/BTC"),
+                4,
+                Formula::Invert(ValueRef {
+                    oracle_id: block_oracle_id,
+                    value_id: 0
+                })
+            ),
+            Error::WrongInputClock
+        );
+
+        assert_ok!(OracleModule::add_derived_value(
+            Origin::signed(ALICE),
+            oracle_id,
+            to_raw("EUR/JPY"),
+            2,
+            eur_jpy
+        ));
+        assert_ok!(OracleModule::add_derived_value(
+            Origin::signed(ALICE),
+            oracle_id,
+            to_raw("EUR/GBP"),
+            4,
+            Formula::Divide(value(2), value(3))
+        ));
+        assert_ok!(OracleModule::add_derived_value(
+            Origin::signed(ALICE),
+            oracle_id,
+            to_raw("1/BTC"),
+            4,
+            Formula::Invert(value(0))
+        ));
+        assert_eq!(OracleModule::derived_values(oracle_id).len(), 3);
+
+        assert_err!(
+            OracleModule::get_derived_value(&JUDY, oracle_id, 0),
+            Error::NotCalculatedValue
+        );
+
+        [CAROL, BOB, EVE].iter().for_each(|&account| {
+            assert_ok!(OracleModule::push(
+                Origin::signed(account),
+                oracle_id,
+                vec![0, 0, 10850, 12500, 0, 0, 15025]
+            ));
+        });
+
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        [0, 2, 3].iter().for_each(|&value_id| {
+            assert_ok!(OracleModule::calculate(
+                Origin::signed(ALICE),
+                oracle_id,
+                value_id
+            ));
+        });
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 2);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 6));
+
+        assert_eq!(
+            OracleModule::get_derived_value(&JUDY, oracle_id, 0),
+            Ok((16302, AGGREGATION_PERIOD + 1))
+        );
+        assert_eq!(
+            OracleModule::get_derived_value(&JUDY, oracle_id, 1),
+            Ok((8680, AGGREGATION_PERIOD + 1))
+        );
+        assert_err!(
+            OracleModule::get_derived_value(&JUDY, oracle_id, 2),
+            Error::FormulaError
+        );
+        assert_err!(
+            OracleModule::get_derived_value(&JUDY, oracle_id, 3),
+            Error::WrongValueId
+        );
+
+        // Ids of derived values are kept after removal
+        assert_ok!(OracleModule::remove_derived_value(
+            Origin::signed(ALICE),
+            oracle_id,
+            0
+        ));
+        assert_err!(
+            OracleModule::get_derived_value(&JUDY, oracle_id, 0),
+            Error::WrongValueId
+        );
+        assert_eq!(
+            OracleModule::get_derived_value(&JUDY, oracle_id, 1),
+            Ok((8680, AGGREGATION_PERIOD + 1))
+        );
+        assert_err!(
+            OracleModule::remove_derived_value(Origin::signed(ALICE), oracle_id, 0),
+            Error::WrongValueId
+        );
+        assert_ok!(OracleModule::add_derived_value(
+            Origin::signed(ALICE),
+            oracle_id,
+            to_raw("1/BTC"),
+            4,
+            Formula::Invert(value(0))
+        ));
+        assert!(OracleModule::derived_values(oracle_id).contains_key(&3));
+
+        // Count of derived values is limited
+        (OracleModule::derived_values(oracle_id).len()..MAX_DERIVED_VALUES).for_each(|_| {
+            assert_ok!(OracleModule::add_derived_value(
+                Origin::signed(ALICE),
+                oracle_id,
+                to_raw("1/BTC"),
+                4,
+                Formula::Invert(value(0))
+            ));
+        });
+        assert_err!(
+            OracleModule::add_derived_value(
+                Origin::signed(ALICE),
+                oracle_id,
+                to_raw("1/BTC"),
+                4,
+                Formula::Invert(value(0))
+            ),
+            Error::TooManyDerivedValues
+        );
+    });
+}
+
+#[test]
+fn fallback() {
+    new_test_ext().execute_with(|| {
+        let primary = ValueRef {
+            oracle_id: OracleModule::next_oracle_id(),
+            value_id: 0,
+        };
+        let primary_table = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+        let secondary = ValueRef {
+            oracle_id: OracleModule::next_oracle_id(),
+            value_id: 0,
+        };
+        let secondary_table = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+        let decimals = vec![6; get_asset_names().len()];
+        assert_ok!(crate::Oracles::<Test>::mutate(
+            secondary.oracle_id,
+            |oracle| oracle.set_decimals(decimals)
+        ));
+
+        self_votes(primary_table, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+        self_votes(secondary_table, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        let set_fallback = |who, value: ValueRef<u32>, fallback| {
+            OracleModule::set_fallback(
+                Origin::signed(who),
+                value.oracle_id,
+                value.value_id,
+                fallback,
+            )
+        };
+        assert_err!(
+            set_fallback(BOB, primary, Some(secondary)),
+            Error::NotOracleOwner
+        );
+        assert_err!(
+            set_fallback(ALICE, primary, Some(primary)),
+            Error::WrongFallback
+        );
+        assert_err!(
+            set_fallback(
+                ALICE,
+                primary,
+                Some(ValueRef {
+                    oracle_id: secondary.oracle_id,
+                    value_id: 100
+                })
+            ),
+            Error::WrongValueId
+        );
+        assert_err!(
+            set_fallback(
+                ALICE,
+                primary,
+                Some(ValueRef {
+                    oracle_id: secondary.oracle_id + 1,
+                    value_id: 0
+                })
+            ),
+            Error::WrongOracleId
+        );
+        assert_ok!(set_fallback(ALICE, primary, Some(secondary)));
+        assert_eq!(
+            OracleModule::fallback(primary.oracle_id, primary.value_id),
+            Some(secondary)
+        );
+        assert_err!(
+            set_fallback(ALICE, secondary, Some(primary)),
+            Error::WrongFallback
+        );
+
+        assert_ok!(OracleModule::set_heartbeat(
+            Origin::signed(ALICE),
+            primary.oracle_id,
+            0,
+            Some(1)
+        ));
+        assert_err!(
+            OracleModule::get_served_value(&JUDY, primary.oracle_id, 0),
+            Error::NotCalculatedValue
+        );
+
+        let push_and_calculate = |value: ValueRef<u32>, offset| {
+            [CAROL, BOB, EVE].iter().for_each(|&account| {
+                assert_ok!(OracleModule::push(
+                    Origin::signed(account),
+                    value.oracle_id,
+                    get_asset_value(0, offset)
+                ));
+            });
+            let now = TimestampModule::get();
+            TimestampModule::set_timestamp(now + AGGREGATION_PERIOD + 1);
+            assert_ok!(OracleModule::calculate(
+                Origin::signed(ALICE),
+                value.oracle_id,
+                value.value_id
+            ));
+            TimestampModule::set_timestamp(now);
+        };
+
+        push_and_calculate(secondary, 100);
+        assert_eq!(
+            OracleModule::get_served_value(&JUDY, primary.oracle_id, 0),
+            Ok(ServedValue {
+                served_by: secondary,
+                value: get_asset_value(0, 100)[0],
+                decimals: 6,
+                last_changed: AGGREGATION_PERIOD + 1,
+            })
+        );
+
+        push_and_calculate(primary, 0);
+        assert_eq!(
+            OracleModule::get_served_value(&JUDY, primary.oracle_id, 0),
+            Ok(ServedValue {
+                served_by: primary,
+                value: get_asset_value(0, 0)[0],
+                decimals: 0,
+                last_changed: AGGREGATION_PERIOD + 1,
+            })
+        );
+
+        // Primary value is older than its max age
+        assert_err!(
+            OracleModule::set_max_age(Origin::signed(BOB), primary.oracle_id, 0, Some(10)),
+            Error::NotOracleOwner
+        );
+        assert_err!(
+            OracleModule::set_max_age(Origin::signed(ALICE), primary.oracle_id, 100, Some(10)),
+            Error::WrongValueId
+        );
+        assert_ok!(OracleModule::set_max_age(
+            Origin::signed(ALICE),
+            primary.oracle_id,
+            0,
+            Some(10)
+        ));
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 11);
+        assert_eq!(
+            OracleModule::get_served_value(&JUDY, primary.oracle_id, 0)
+                .map(|served| served.served_by),
+            Ok(primary)
+        );
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 12);
+        assert_eq!(
+            OracleModule::get_served_value(&JUDY, primary.oracle_id, 0)
+                .map(|served| served.served_by),
+            Ok(secondary)
+        );
+        assert_ok!(OracleModule::set_max_age(
+            Origin::signed(ALICE),
+            primary.oracle_id,
+            0,
+            None
+        ));
+        assert_eq!(
+            OracleModule::get_served_value(&JUDY, primary.oracle_id, 0)
+                .map(|served| served.served_by),
+            Ok(primary)
+        );
+
+        // Primary value missed its heartbeat
+        TimestampModule::set_timestamp(2 * CALCULATION_PERIOD);
+        assert_eq!(
+            OracleModule::get_served_value(&JUDY, primary.oracle_id, 0)
+                .map(|served| served.served_by),
+            Ok(secondary)
+        );
+
+        assert_ok!(set_fallback(ALICE, primary, None));
+        assert_err!(
+            OracleModule::get_served_value(&JUDY, primary.oracle_id, 0),
+            Error::ValueDegraded
+        );
+    });
+}
+
+#[test]
+fn oracle_update_hook() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        [(CAROL, 0), (BOB, 10), (EVE, 20)]
+            .iter()
+            .for_each(|&(account, offset)| {
+                assert_ok!(OracleModule::push(
+                    Origin::signed(account),
+                    oracle_id,
+                    get_asset_value(0, offset)
+                ));
+            });
+
+        // Failed calculation doesn't notify
+        assert_err!(
+            OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0),
+            Error::NotCalculateTime
+        );
+        assert_eq!(UpdateRecorder::take(), vec![]);
+
+        let now = AGGREGATION_PERIOD + 1;
+        TimestampModule::set_timestamp(now);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 2));
+
+        let expected = get_median_values(0, vec![0, 10, 20]);
+        assert_eq!(
+            UpdateRecorder::take(),
+            vec![
+                (oracle_id, 0, expected[0], now),
+                (oracle_id, 2, expected[2], now)
+            ]
+        );
+    });
+}
+
+#[test]
+fn subscription() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        let table_id = TablescoreModule::next_table_id();
+        assert_ok!(create_oracle(3));
+        self_votes(table_id, vec![(CAROL, 99), (BOB, 100), (EVE, 101)]);
+
+        // Oracle is open for reading until it becomes permissioned
+        assert!(OracleModule::is_subscribed(oracle_id, &JUDY));
+        assert_err!(
+            OracleModule::subscribe(Origin::signed(FRANK), oracle_id, 2),
+            Error::NotSubscribable
+        );
+        assert_err!(
+            OracleModule::set_subscription_fee(Origin::signed(BOB), oracle_id, ASSET_ID, 210),
+            Error::NotOracleOwner
+        );
+        assert_ok!(OracleModule::set_subscription_fee(
+            Origin::signed(ALICE),
+            oracle_id,
+            ASSET_ID,
+            210
+        ));
+        assert_err!(
+            OracleModule::set_subscription_fee(Origin::signed(ALICE), oracle_id, 1, 210),
+            Error::WrongSubscriptionAsset
+        );
+        assert!(!OracleModule::is_subscribed(oracle_id, &JUDY));
+
+        assert_err!(
+            OracleModule::subscribe(Origin::signed(FRANK), oracle_id, 0),
+            Error::WrongSubscriptionPeriods
+        );
+        assert_ok!(OracleModule::subscribe(Origin::signed(FRANK), oracle_id, 1));
+        assert_ok!(OracleModule::subscribe(Origin::signed(FRANK), oracle_id, 1));
+        assert_eq!(
+            OracleModule::subscription(oracle_id, FRANK),
+            Some(Subscription { start: 0, end: 2 })
+        );
+        assert_eq!(
+            AssetsModule::free_balance(&ASSET_ID, &FRANK),
+            TOTAL_BALANCE - 420
+        );
+        assert_eq!(
+            AssetsModule::free_balance(&ASSET_ID, &OracleModule::account_id(oracle_id)),
+            420
+        );
+        assert!(OracleModule::is_subscribed(oracle_id, &FRANK));
+
+        [(CAROL, 0), (BOB, 10), (EVE, 20)]
+            .iter()
+            .for_each(|&(account, offset)| {
+                assert_ok!(OracleModule::push(
+                    Origin::signed(account),
+                    oracle_id,
+                    get_asset_value(0, offset)
+                ));
+            });
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+
+        // Value fee 210 / 7 is shared among 3 sources
+        [CAROL, BOB, EVE].iter().for_each(|source| {
+            assert_eq!(OracleModule::fees(oracle_id, source), 10);
+        });
+        assert_eq!(OracleModule::subscription_fees(oracle_id, 0).left, 180);
+        assert_eq!(OracleModule::subscription_fees(oracle_id, 1).left, 210);
+
+        // Fees of value are shared once per period
+        OracleModule::share_fees(oracle_id, 0, 0, 7, &[(CAROL, Some(Perbill::zero()))]);
+        assert_eq!(OracleModule::fees(oracle_id, CAROL), 10);
+        assert_eq!(OracleModule::subscription_fees(oracle_id, 0).left, 180);
+
+        assert_eq!(
+            OracleModule::get_served_value(&FRANK, oracle_id, 0).map(|served| served.value),
+            Ok(get_median_values(0, vec![0, 10, 20])[0])
+        );
+        assert_err!(
+            OracleModule::get_served_value(&JUDY, oracle_id, 0),
+            Error::NotSubscribed
+        );
+
+        // Derived values of permissioned oracle are read by subscribers
+        let value = ValueRef {
+            oracle_id,
+            value_id: 0,
+        };
+        assert_ok!(OracleModule::add_derived_value(
+            Origin::signed(ALICE),
+            oracle_id,
+            to_raw("1/BTC"),
+            4,
+            Formula::Invert(value)
+        ));
+        assert_ok!(OracleModule::get_derived_value(&FRANK, oracle_id, 0));
+        assert_err!(
+            OracleModule::get_derived_value(&JUDY, oracle_id, 0),
+            Error::NotSubscribed
+        );
+
+        // Only owner of permissioned oracle can refer it in other oracles
+        let other_id = OracleModule::next_oracle_id();
+        assert_ok!(OracleModule::create_oracle(
+            Origin::signed(BOB),
+            to_raw(ORACLE_NAME),
+            3,
+            None,
+            CALCULATION_PERIOD,
+            AGGREGATION_PERIOD,
+            ASSET_ID,
+            get_asset_names(),
+            vec![0; get_asset_names().len()],
+            ClockSource::Timestamp,
+            PeriodAlignment::Creation,
+        ));
+        assert_err!(
+            OracleModule::set_fallback(Origin::signed(BOB), other_id, 0, Some(value)),
+            Error::NotOracleOwner
+        );
+        assert_err!(
+            OracleModule::add_derived_value(
+                Origin::signed(BOB),
+                other_id,
+                to_raw("1/BTC"),
+                4,
+                Formula::Invert(value)
+            ),
+            Error::NotOracleOwner
+        );
+
+        let bob_balance = AssetsModule::free_balance(&ASSET_ID, &BOB);
+        assert_ok!(OracleModule::claim_fees(Origin::signed(BOB), oracle_id));
+        assert_eq!(
+            AssetsModule::free_balance(&ASSET_ID, &BOB),
+            bob_balance + 10
+        );
+        assert_err!(
+            OracleModule::claim_fees(Origin::signed(BOB), oracle_id),
+            Error::NothingToClaim
+        );
+
+        // Subscription expires after paid periods
+        TimestampModule::set_timestamp(CALCULATION_PERIOD);
+        assert!(OracleModule::is_subscribed(oracle_id, &FRANK));
+        TimestampModule::set_timestamp(2 * CALCULATION_PERIOD);
+        assert!(!OracleModule::is_subscribed(oracle_id, &FRANK));
+
+        // Fees left in periods 0 and 1 are carried over to period 2 with calculation
+        [CAROL, BOB, EVE].iter().for_each(|&account| {
+            assert_ok!(OracleModule::push(
+                Origin::signed(account),
+                oracle_id,
+                get_asset_value(0, 0)
+            ));
+        });
+        TimestampModule::set_timestamp(2 * CALCULATION_PERIOD + AGGREGATION_PERIOD + 1);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 0));
+
+        // Value fee 390 / 7 is shared among 3 sources
+        assert_eq!(OracleModule::fees(oracle_id, CAROL), 10 + 18);
+        assert_eq!(OracleModule::fees(oracle_id, BOB), 18);
+        assert_eq!(OracleModule::subscription_fees(oracle_id, 2).left, 390 - 54);
+        assert_eq!(
+            OracleModule::subscription_fees(oracle_id, 0),
+            Default::default()
+        );
+        assert_eq!(OracleModule::fee_periods(oracle_id), vec![2]);
+
+        // Lazy calculation in period 3 shares fees of period 2, whose data it uses
+        TimestampModule::set_timestamp(3 * CALCULATION_PERIOD + 1);
+        assert_ok!(OracleModule::subscribe(Origin::signed(FRANK), oracle_id, 1));
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), oracle_id, 1));
+        assert_eq!(OracleModule::fees(oracle_id, CAROL), 10 + 18 + 18);
+        assert_eq!(
+            OracleModule::subscription_fees(oracle_id, 2).left,
+            390 - 2 * 54
+        );
+        assert_eq!(
+            OracleModule::subscription_fees(oracle_id, 2).paid_values,
+            vec![0, 1]
+        );
+        assert_eq!(OracleModule::subscription_fees(oracle_id, 3).left, 210);
+        assert_eq!(OracleModule::fee_periods(oracle_id), vec![2, 3]);
+    });
+}
+
+fn genesis_oracle(source_mode: SourceMode, sources: Vec<AccountId>) -> GenesisOracle {
+    GenesisOracle {
+        owner: ALICE,
+        name: to_raw(ORACLE_NAME),
+        source_limit: 3,
+        quorum: None,
+        period: CALCULATION_PERIOD,
+        aggregate_period: AGGREGATION_PERIOD,
+        asset_id: ASSET_ID,
+        values_names: get_asset_names(),
+        values_decimals: vec![0; get_asset_names().len()],
+        clock: ClockSource::Timestamp,
+        alignment: PeriodAlignment::Creation,
+        source_mode,
+        sources,
+        values: vec![],
+    }
+}
+
+#[test]
+fn genesis() {
+    let fixed = GenesisOracle {
+        values: get_asset_value(0, 0),
+        ..genesis_oracle(SourceMode::Fixed, vec![CAROL, BOB, EVE])
+    };
+    let voted = genesis_oracle(SourceMode::Tablescore, vec![]);
+
+    new_test_ext_with_oracles(vec![fixed, voted]).execute_with(|| {
+        assert_eq!(OracleModule::next_oracle_id(), 2);
+        assert_eq!(TablescoreModule::next_table_id(), 2);
+
+        let info = OracleModule::oracle_info(0).unwrap();
+        assert_eq!(info.owner, Some(ALICE));
+        assert_eq!(info.source_mode, SourceMode::Fixed);
+        assert_eq!(
+            OracleModule::oracle_info(1).unwrap().source_mode,
+            SourceMode::Tablescore
+        );
+
+        let value = OracleModule::value_info(0, 2).unwrap();
+        assert_eq!(
+            (value.value, value.last_changed),
+            (Some(EUR_USD_DATA[0]), Some(0))
+        );
+        assert_eq!(OracleModule::value_info(1, 2).unwrap().value, None);
+
+        // Fixed sources push without voting in tablescore
+        [CAROL, BOB, EVE].iter().for_each(|&account| {
+            assert_ok!(OracleModule::push(
+                Origin::signed(account),
+                0,
+                get_asset_value(1, 0)
+            ));
+        });
+        assert_err!(
+            OracleModule::push(Origin::signed(JUDY), 0, get_asset_value(1, 0)),
+            Error::AccountPermissionDenied
+        );
+        TimestampModule::set_timestamp(AGGREGATION_PERIOD + 1);
+        assert_ok!(OracleModule::calculate(Origin::signed(ALICE), 0, 2));
+        let value = OracleModule::value_info(0, 2).unwrap();
+        assert_eq!(
+            (value.value, value.last_changed),
+            (Some(EUR_USD_DATA[1]), Some(AGGREGATION_PERIOD + 1))
+        );
+
+        assert_err!(
+            OracleModule::create_genesis_oracle(genesis_oracle(SourceMode::Tablescore, vec![BOB])),
+            Error::WrongSourceMode
+        );
+    });
+}
+
+#[test]
+fn fixed_sources() {
+    new_test_ext().execute_with(|| {
+        let oracle_id = OracleModule::next_oracle_id();
+        assert_ok!(create_oracle(3));
+
+        // Oracle owner can't fix sources
+        assert!(OracleModule::set_fixed_sources(
+            Origin::signed(ALICE),
+            oracle_id,
+            Some(vec![BOB, CAROL, EVE])
+        )
+        .is_err());
+        assert_err!(
+            OracleModule::set_fixed_sources(Origin::ROOT, oracle_id + 1, Some(vec![BOB])),
+            Error::WrongOracleId
+        );
+        assert_err!(
+            OracleModule::set_fixed_sources(Origin::ROOT, oracle_id, Some(vec![BOB, CAROL])),
+            Error::NotEnoughSources
+        );
+        assert_ok!(OracleModule::set_fixed_sources(
+            Origin::ROOT,
+            oracle_id,
+            Some(vec![BOB, CAROL, EVE])
+        ));
+        assert_eq!(
+            OracleModule::oracle_info(oracle_id).unwrap().source_mode,
+            SourceMode::Fixed
+        );
+        assert_ok!(OracleModule::push(
+            Origin::signed(BOB),
+            oracle_id,
+            get_asset_value(0, 0)
+        ));
+
+        // Sources are updated from tablescore without votes
+        assert_ok!(OracleModule::set_fixed_sources(
+            Origin::ROOT,
+            oracle_id,
+            None
+        ));
+        assert_err!(
+            OracleModule::push(Origin::signed(BOB), oracle_id, get_asset_value(0, 0)),
+            Error::NotEnoughSources
+        );
+    });
+}
+
+#[test]
+fn migration_from_v0() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(OracleModule::storage_version(), crate::STORAGE_VERSION);
+
+        // Oracle in layout before storage versioning, value 0 is calculated in period 0,
+        // values of period 1 are pushed
+        let value = |value: u128, moment: u128| (Some(value), Some(moment));
+        let empty: (Option<u128>, Option<u128>) = (None, None);
+        let sources: BTreeMap<AccountId, _> = vec![
+            (BOB, vec![value(110, 620), value(210, 620)]),
+            (CAROL, vec![value(120, 630), empty]),
+        ]
+        .into_iter()
+        .collect();
+        let prev_period_source: BTreeMap<AccountId, _> = vec![
+            (BOB, vec![None, Some(value(200, 10))]),
+            (CAROL, vec![None, Some(value(202, 20))]),
+        ]
+        .into_iter()
+        .collect();
+        let fixture = (
+            to_raw(ORACLE_NAME),
+            7u32,
+            2u8,
+            (0u128, CALCULATION_PERIOD, AGGREGATION_PERIOD, Some(0u128)),
+            sources,
+            vec![to_raw("EUR/USD"), to_raw("GBP/USD")],
+            vec![value(100, 541), empty],
+            Some(1u128),
+            prev_period_source,
+        )
+            .encode();
+
+        unhashed::put_raw(&crate::Oracles::<Test>::hashed_key_for(0), &fixture);
+        unhashed::put_raw(&crate::Oracles::<Test>::hashed_key_for(1), &[1, 2, 3]);
+        crate::OracleIdSequence::<Test>::put(2);
+        crate::StorageVersion::kill();
+        assert_eq!(OracleModule::storage_version(), 0);
+
+        // Version isn't changed, while some oracles fail to migrate
+        crate::migration::migrate::<Test>();
+        assert_eq!(OracleModule::storage_version(), 0);
+        let migrated = crate::Oracles::<Test>::get(0);
+
+        unhashed::put_raw(&crate::Oracles::<Test>::hashed_key_for(1), &fixture);
+        crate::migration::migrate::<Test>();
+        assert_eq!(OracleModule::storage_version(), crate::STORAGE_VERSION);
+        assert_eq!(crate::Oracles::<Test>::get(0), migrated);
+        assert_eq!(crate::Oracles::<Test>::get(1), migrated);
+
+        // Root sets owners of migrated oracles
+        assert_eq!(OracleModule::oracle_owner(0), None);
+        assert!(OracleModule::set_oracle_owner(Origin::signed(ALICE), 0, ALICE).is_err());
+        assert_err!(
+            OracleModule::set_oracle_owner(Origin::ROOT, 2, ALICE),
+            Error::WrongOracleId
+        );
+        assert_ok!(OracleModule::set_oracle_owner(Origin::ROOT, 0, ALICE));
+        assert_eq!(OracleModule::oracle_owner(0), Some(ALICE));
+
+        let info = OracleModule::oracle_info(0).unwrap();
+        assert_eq!(info.name, to_raw(ORACLE_NAME));
+        assert_eq!(info.source_limit, 2);
+        assert_eq!(
+            (info.period, info.aggregate_part),
+            (CALCULATION_PERIOD, AGGREGATION_PERIOD)
+        );
+        assert_eq!(info.clock, ClockSource::Timestamp);
+        assert_eq!(
+            info.values_names,
+            vec![to_raw("EUR/USD"), to_raw("GBP/USD")]
+        );
+
+        let value_info = OracleModule::value_info(0, 0).unwrap();
+        assert_eq!(value_info.value, Some(100));
+        assert_eq!(value_info.last_changed, Some(541));
+        assert_eq!(value_info.observed, Some(541));
+
+        let mut oracle = OracleModule::oracles(0);
+        assert_eq!(*oracle.get_table(), 7);
+        assert!(oracle.is_source(&BOB) && oracle.is_source(&CAROL));
+
+        // Pushed values of period 1 and data of period 0 are kept
+        assert_eq!(OracleModule::history_periods(0), vec![0]);
+        assert!(OracleModule::history(0, 0).is_some());
+        assert_eq!(
+            oracle.calculate_value(0, CALCULATION_PERIOD + AGGREGATION_PERIOD + 1),
+            Ok(115)
+        );
+        assert_eq!(oracle.calculate_value(1, CALCULATION_PERIOD + 1), Ok(201));
+
+        // Migrated storage is not migrated again
+        let migrated = crate::Oracles::<Test>::get(0);
+        crate::migration::migrate::<Test>();
+        assert_eq!(crate::Oracles::<Test>::get(0), migrated);
+    });
+}
+
+#[test]
+fn migration_from_v0_decodable_as_current() {
+    new_test_ext().execute_with(|| {
+        // Oracle in layout before storage versioning, whose prefix is decodable in the
+        // current layout: zeroed name of value fills the tail of current oracle
+        let fixture = (
+            to_raw(ORACLE_NAME),
+            7u32,
+            2u8,
+            (0u128, CALCULATION_PERIOD, AGGREGATION_PERIOD, None::<u128>),
+            BTreeMap::<AccountId, Vec<(Option<u128>, Option<u128>)>>::new(),
+            vec![vec![0u8; 60]],
+            vec![(None::<u128>, None::<u128>)],
+            None::<u128>,
+            BTreeMap::<AccountId, Vec<Option<(Option<u128>, Option<u128>)>>>::new(),
+        )
+            .encode();
+        assert!(
+            crate::oracle::Oracle::<TableId, u128, u128, AccountId>::decode(&mut &fixture[..])
+                .is_ok()
+        );
+
+        unhashed::put_raw(&crate::Oracles::<Test>::hashed_key_for(0), &fixture);
+        crate::OracleIdSequence::<Test>::put(1);
+        crate::StorageVersion::kill();
+
+        crate::migration::migrate::<Test>();
+        assert_eq!(OracleModule::storage_version(), crate::STORAGE_VERSION);
+
+        let info = OracleModule::oracle_info(0).unwrap();
+        assert_eq!(info.name, to_raw(ORACLE_NAME));
+        assert_eq!(
+            (info.period, info.aggregate_part),
+            (CALCULATION_PERIOD, AGGREGATION_PERIOD)
+        );
+        assert_eq!(info.values_names, vec![vec![0u8; 60]]);
+        assert_eq!(*OracleModule::oracles(0).get_table(), 7);
+    });
+}
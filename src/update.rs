@@ -0,0 +1,16 @@
+/// Handler of values calculated in oracles
+///
+/// Can be used by consumer pallets to react on new values in the same block.
+/// Implemented for tuples, so several handlers can be set in runtime.
+pub trait OnOracleUpdate<OracleId, ValueType, Moment> {
+    fn on_oracle_update(oracle_id: OracleId, value_id: u8, value: ValueType, moment: Moment);
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<OracleId: Clone, ValueType: Clone, Moment: Clone> OnOracleUpdate<OracleId, ValueType, Moment>
+    for Tuple
+{
+    fn on_oracle_update(oracle_id: OracleId, value_id: u8, value: ValueType, moment: Moment) {
+        for_tuples!( #( Tuple::on_oracle_update(oracle_id.clone(), value_id, value.clone(), moment.clone()); )* );
+    }
+}